@@ -0,0 +1,104 @@
+#![feature(test)]
+extern crate hdrhistogram;
+extern crate rand;
+extern crate test;
+
+use self::test::Bencher;
+use hdrhistogram::concurrent::recorder;
+use hdrhistogram::concurrent::{ResizableHistogram, ShardedRecorder};
+use rand::Rng;
+use std::thread;
+
+const VALUES_PER_THREAD: usize = 100_000;
+
+type Static = hdrhistogram::static_histogram!(1, 1 << 40, 3);
+
+fn random_values() -> Vec<u64> {
+    let mut rng = rand::thread_rng();
+    (0..VALUES_PER_THREAD).map(|_| rng.gen_range(1..1 << 40)).collect()
+}
+
+// every thread records the same values, so the time per iteration stays flat
+// as long as recording scales with the number of threads
+fn record_slices_from_threads<F: Fn(&[u64]) + Sync>(b: &mut Bencher, threads: usize, record: F) {
+    let values = random_values();
+    b.iter(|| {
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| record(&values));
+            }
+        })
+    })
+}
+
+fn record_from_threads<F: Fn(u64) + Sync>(b: &mut Bencher, threads: usize, record: F) {
+    record_slices_from_threads(b, threads, |values| {
+        for v in values.iter() {
+            record(*v);
+        }
+    })
+}
+
+macro_rules! scaling_benches {
+    ($setup:expr, $($name:ident => $threads:expr),*) => {
+        $(
+            #[bench]
+            fn $name(b: &mut Bencher) {
+                let recordable = $setup;
+                record_from_threads(b, $threads, |v| recordable.record_value(v).unwrap());
+            }
+        )*
+    };
+}
+
+mod static_histogram {
+    use super::*;
+
+    scaling_benches!(Static::with_low_high_sigvdig(1, 1 << 40, 3).unwrap(),
+        threads_01 => 1, threads_02 => 2, threads_04 => 4, threads_08 => 8, threads_16 => 16);
+}
+
+mod resizable_histogram {
+    use super::*;
+
+    scaling_benches!(ResizableHistogram::with_low_high_sigvdig(1, 1 << 40, 3).unwrap(),
+        threads_01 => 1, threads_02 => 2, threads_04 => 4, threads_08 => 8, threads_16 => 16);
+}
+
+mod resizable_recorder {
+    use super::*;
+
+    scaling_benches!(recorder::resizable_with_low_high_sigvdig(1, 1 << 40, 3).unwrap(),
+        threads_01 => 1, threads_02 => 2, threads_04 => 4, threads_08 => 8, threads_16 => 16);
+}
+
+mod sharded_recorder {
+    use super::*;
+
+    scaling_benches!(ShardedRecorder::with_low_high_sigvdig(1, 1 << 40, 3).unwrap(),
+        threads_01 => 1, threads_02 => 2, threads_04 => 4, threads_08 => 8, threads_16 => 16);
+}
+
+mod batched_resizable_recorder {
+    use super::*;
+
+    fn record_batches(b: &mut Bencher, threads: usize) {
+        let recorder = recorder::resizable_with_low_high_sigvdig(1, 1 << 40, 3).unwrap();
+        record_slices_from_threads(b, threads, |values| recorder.record_values(values).unwrap());
+    }
+
+    #[bench]
+    fn threads_01(b: &mut Bencher) {
+        record_batches(b, 1);
+    }
+
+    #[bench]
+    fn threads_04(b: &mut Bencher) {
+        record_batches(b, 4);
+    }
+
+    #[bench]
+    fn threads_16(b: &mut Bencher) {
+        record_batches(b, 16);
+    }
+}
@@ -45,6 +45,23 @@ fn bench_percentile(b: &mut Bencher) {
     })
 }
 
+#[bench]
+fn bench_percentiles_batch(b: &mut Bencher) {
+    let mut h = Histogram::<u64>::with_low_high_sigvdig(1, u64::MAX, 3).unwrap();
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..1000000 {
+        h.record_value(rng.gen()).unwrap()
+    }
+
+    let percentiles = [50.0, 75.0, 90.0, 95.0, 99.0, 99.9, 99.99, 100.0];
+    b.iter(|| {
+        for _ in 1..1000 {
+            h.get_values_at_percentiles(&percentiles);
+        }
+    })
+}
+
 #[bench]
 fn percentile_iter(b: &mut Bencher) {
     let mut histogram = Histogram::<u64>::with_low_high_sigvdig(1, u64::MAX, 3).unwrap();
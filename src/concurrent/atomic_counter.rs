@@ -0,0 +1,79 @@
+use std::sync::atomic::{AtomicU16, AtomicU32, AtomicU64, Ordering};
+
+/// An atomic integer the concurrent histograms can keep their counts in, the atomic counterpart
+/// of `Counter`. Narrower counters trade the largest count a single bucket can hold for memory.
+///
+/// # Safety
+/// Counts arrays are allocated zeroed and freed without dropping their elements, so implementors
+/// must be valid when zero-initialized and must not need drop.
+pub unsafe trait AtomicCounter: Send + Sync {
+    /// The largest count a single counter can hold.
+    const MAX: u64;
+
+    fn load(&self, ordering: Ordering) -> u64;
+
+    /// Stores `count`, which the caller guarantees to be at most `MAX`.
+    fn store(&self, count: u64, ordering: Ordering);
+
+    /// Adds `count`, or leaves the counter untouched and returns `false` if the sum would not
+    /// fit.
+    fn try_add(&self, count: u64, ordering: Ordering) -> bool;
+
+    fn word_size() -> u8;
+}
+
+unsafe impl AtomicCounter for AtomicU64 {
+    const MAX: u64 = u64::MAX;
+
+    #[inline(always)]
+    fn load(&self, ordering: Ordering) -> u64 {
+        AtomicU64::load(self, ordering)
+    }
+    #[inline(always)]
+    fn store(&self, count: u64, ordering: Ordering) {
+        AtomicU64::store(self, count, ordering)
+    }
+    #[inline(always)]
+    fn try_add(&self, count: u64, ordering: Ordering) -> bool {
+        // u64 counts, won't overflow
+        self.fetch_add(count, ordering);
+        true
+    }
+    #[inline(always)]
+    fn word_size() -> u8 {
+        8
+    }
+}
+
+macro_rules! narrow_atomic_counter {
+    ($atomic:ty, $int:ty, $word_size:expr) => {
+        unsafe impl AtomicCounter for $atomic {
+            const MAX: u64 = <$int>::MAX as u64;
+
+            #[inline(always)]
+            fn load(&self, ordering: Ordering) -> u64 {
+                u64::from(<$atomic>::load(self, ordering))
+            }
+            #[inline(always)]
+            fn store(&self, count: u64, ordering: Ordering) {
+                <$atomic>::store(self, count as $int, ordering)
+            }
+            #[inline(always)]
+            fn try_add(&self, count: u64, ordering: Ordering) -> bool {
+                let count = match <$int>::try_from(count) {
+                    Ok(count) => count,
+                    Err(_) => return false,
+                };
+                self.fetch_update(ordering, Ordering::Relaxed, |current| current.checked_add(count))
+                    .is_ok()
+            }
+            #[inline(always)]
+            fn word_size() -> u8 {
+                $word_size
+            }
+        }
+    };
+}
+
+narrow_atomic_counter!(AtomicU32, u32, 4);
+narrow_atomic_counter!(AtomicU16, u16, 2);
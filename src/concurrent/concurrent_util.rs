@@ -1,45 +1,53 @@
 use std::sync::atomic::{Ordering, AtomicU64};
 use std::cell::UnsafeCell;
+use std::ops::Deref;
+use crate::concurrent::AtomicCounter;
+use crate::concurrent::inline_backing_array::InlineBackingArray;
 use crate::core::*;
 
+/// Keeps a frequently written atomic on its own cache line, so writes to it don't invalidate the
+/// lines holding the settings and counts pointer every record reads. 128 bytes covers CPUs that
+/// prefetch adjacent line pairs.
+#[repr(align(128))]
+pub(crate) struct CachePadded<T>(pub(crate) T);
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+// Callers check the sampled value with a relaxed load first, so these only write when the
+// extreme actually moves.
 #[inline(always)]
 pub fn update_max_value(settings: &UnsafeCell<HistogramSettings>, raw_max_value: &AtomicU64, value: u64) {
     let internal_value = value | unsafe { (*settings.get()).unit_magnitude_mask };
-    let mut sampled_max_value = raw_max_value.load(Ordering::Relaxed);
-    while sampled_max_value < internal_value {
-        match raw_max_value.compare_exchange_weak(
-            sampled_max_value,
-            internal_value,
-            Ordering::Relaxed,
-            Ordering::Relaxed,
-        ) {
-            Ok(_) => break,
-            Err(_) => (),
-        }
-        sampled_max_value = raw_max_value.load(Ordering::Relaxed);
-    }
+    raw_max_value.fetch_max(internal_value, Ordering::Relaxed);
 }
 
 #[inline(always)]
 pub fn update_min_non_zero_value(settings: &UnsafeCell<HistogramSettings>, raw_min_non_zero_value: &AtomicU64, value: u64) {
-    unsafe {
-        let settings = settings.get();
-        if value <= (*settings).unit_magnitude_mask {
-            return;
-        }
-        let internal_value = value & !(*settings).unit_magnitude_mask;
-        let mut sampled_min_value = raw_min_non_zero_value.load(Ordering::Relaxed);
-    while internal_value < sampled_min_value {
-        match raw_min_non_zero_value.compare_exchange_weak(
-            sampled_min_value,
-            internal_value,
-                Ordering::Relaxed,
-                Ordering::Relaxed,
-            ) {
-                Ok(_) => break,
-                Err(_) => (),
-            }
-            sampled_min_value = raw_min_non_zero_value.load(Ordering::Relaxed);
-        }
+    let unit_magnitude_mask = unsafe { (*settings.get()).unit_magnitude_mask };
+    if value <= unit_magnitude_mask {
+        return;
     }
+    raw_min_non_zero_value.fetch_min(value & !unit_magnitude_mask, Ordering::Relaxed);
+}
+
+#[inline(always)]
+pub fn add_count<C: AtomicCounter>(counter: &C, count: u64) -> Result<(), RecordError> {
+    if counter.try_add(count, Ordering::Relaxed) {
+        Ok(())
+    } else {
+        Err(RecordError::CountOverflow)
+    }
+}
+
+// sums the whole array, over its own length
+pub(crate) fn sum_counts<C: AtomicCounter>(counts: &InlineBackingArray<C>) -> u64 {
+    (0..counts.length())
+        .map(|i| unsafe { counts.get_unchecked(i) }.load(Ordering::Relaxed))
+        .sum()
 }
@@ -1,5 +1,6 @@
+use crate::concurrent::recordable_histogram::RecordableHistogram;
 use crate::concurrent::ResizableHistogram;
-use crate::core::{DoubleCreationError, OverflowPolicy, ReadableHistogram, RecordError, SaturateOnOverflow, ThrowOnOverflow};
+use crate::core::{DoubleCreationError, HistogramMetaData, OverflowPolicy, ReadableHistogram, RecordError, SaturateOnOverflow, ThrowOnOverflow};
 use crate::core::util;
 use crate::iteration::RecordedValuesIterator;
 use parking_lot::Mutex;
@@ -192,10 +193,18 @@ impl<P: OverflowPolicy> ConcurrentDoubleHistogramImpl<P> {
     }
 
     pub fn get_value_at_percentile(&self, percentile: f64) -> f64 {
-        let value = get_value_at_percentile_for_histogram(&self.integer_histogram, percentile);
+        let value = util::get_value_at_percentile(&self.integer_histogram, percentile);
         value as f64 * self.integer_to_double_value_conversion_ratio()
     }
 
+    pub fn get_values_at_percentiles(&self, percentiles: &[f64]) -> Vec<f64> {
+        let ratio = self.integer_to_double_value_conversion_ratio();
+        util::get_values_at_percentiles(&self.integer_histogram, percentiles)
+            .into_iter()
+            .map(|value| value as f64 * ratio)
+            .collect()
+    }
+
     pub fn get_percentile_at_or_below_value(&self, value: f64) -> f64 {
         let integer_value = self.to_integer_value_clamped(value);
         get_percentile_at_or_below_value_for_histogram(&self.integer_histogram, integer_value)
@@ -268,6 +277,14 @@ impl<P: OverflowPolicy> ConcurrentDoubleHistogramImpl<P> {
         self.auto_resize.load(Ordering::Relaxed)
     }
 
+    pub fn meta_data(&self) -> &HistogramMetaData {
+        ReadableHistogram::meta_data(&self.integer_histogram)
+    }
+
+    pub fn meta_data_mut(&mut self) -> &mut HistogramMetaData {
+        self.integer_histogram.meta_data_mut()
+    }
+
     pub fn reset(&self) {
         unsafe {
             self.integer_histogram.clear_counts();
@@ -485,9 +502,7 @@ impl<P: OverflowPolicy> ConcurrentDoubleHistogramImpl<P> {
             .store((new_highest * shift_multiplier).to_bits(), Ordering::Relaxed);
 
         let result = (|| {
-            if self.integer_histogram.get_total_count()
-                > self.integer_histogram.get_count_at_index(0).unwrap_or(0)
-            {
+            if self.integer_histogram.has_values_above_zero_bucket() {
                 if self
                     .integer_histogram
                     .shift_values_left(number_of_binary_orders_of_magnitude)
@@ -517,9 +532,7 @@ impl<P: OverflowPolicy> ConcurrentDoubleHistogramImpl<P> {
             .store((new_lowest * shift_multiplier).to_bits(), Ordering::Relaxed);
 
         let result = (|| {
-            if self.integer_histogram.get_total_count()
-                > self.integer_histogram.get_count_at_index(0).unwrap_or(0)
-            {
+            if self.integer_histogram.has_values_above_zero_bucket() {
                 match self
                     .integer_histogram
                     .shift_values_right(number_of_binary_orders_of_magnitude)
@@ -583,37 +596,6 @@ impl<P: OverflowPolicy> ConcurrentDoubleHistogramImpl<P> {
     }
 }
 
-fn get_value_at_percentile_for_histogram<H: ReadableHistogram>(histogram: &H, percentile: f64) -> u64 {
-    let one_below = util::next_below(percentile);
-    let requested_percentile = if one_below > 100.0 {
-        100.0
-    } else if one_below < 0.0 {
-        0.0
-    } else {
-        one_below
-    };
-
-    let total_count = histogram.get_total_count();
-    let fractional_count = (requested_percentile / 100.0) * total_count as f64;
-    let mut count_at_percentile = fractional_count.ceil() as u64;
-    count_at_percentile = std::cmp::max(count_at_percentile, 1);
-
-    let mut total_to_current_index: u64 = 0;
-    for i in 0..histogram.array_length() {
-        total_to_current_index += histogram.unsafe_get_count_at_index(i);
-        if total_to_current_index >= count_at_percentile {
-            let value_at_index = histogram.settings().value_from_index(i);
-            return if percentile == 0.0 {
-                histogram.settings().lowest_equivalent_value(value_at_index)
-            } else {
-                histogram.settings().highest_equivalent_value(value_at_index)
-            };
-        }
-    }
-
-    0
-}
-
 fn get_percentile_at_or_below_value_for_histogram<H: ReadableHistogram>(histogram: &H, value: u64) -> f64 {
     if histogram.get_total_count() == 0 {
         return 100.0;
@@ -0,0 +1,138 @@
+use crate::concurrent::double_histogram::ConcurrentDoubleHistogramImpl;
+use crate::concurrent::writer_reader_phaser::{PhaseFlipGuard, WriterReaderPhaser};
+use crate::core::*;
+use std::mem;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+/// The `f64` counterpart of `Recorder`, recording into a `ConcurrentDoubleHistogramImpl` and
+/// producing double histogram interval samples.
+pub struct DoubleRecorderImpl<P: OverflowPolicy> {
+    recording_phaser: WriterReaderPhaser,
+    active_histogram: AtomicPtr<ConcurrentDoubleHistogramImpl<P>>,
+}
+
+pub type DoubleRecorder = DoubleRecorderImpl<ThrowOnOverflow>;
+pub type SaturatingDoubleRecorder = DoubleRecorderImpl<SaturateOnOverflow>;
+
+pub struct DoubleLockingSample<'a, P: 'a + OverflowPolicy> {
+    parent_recorder: &'a DoubleRecorderImpl<P>,
+    histogram: Box<ConcurrentDoubleHistogramImpl<P>>,
+    guard: PhaseFlipGuard<'a>,
+}
+
+impl<P: OverflowPolicy> DoubleRecorderImpl<P> {
+    /// An auto-resizing recorder, as with `ConcurrentDoubleHistogramImpl::new`.
+    pub fn new(number_of_significant_value_digits: u8) -> Result<Self, DoubleCreationError> {
+        ConcurrentDoubleHistogramImpl::<P>::new(number_of_significant_value_digits).map(DoubleRecorderImpl::from_histogram)
+    }
+
+    pub fn with_highest_to_lowest_value_ratio(
+        highest_to_lowest_value_ratio: u64,
+        number_of_significant_value_digits: u8,
+    ) -> Result<Self, DoubleCreationError> {
+        ConcurrentDoubleHistogramImpl::<P>::with_highest_to_lowest_value_ratio(
+            highest_to_lowest_value_ratio,
+            number_of_significant_value_digits,
+        ).map(DoubleRecorderImpl::from_histogram)
+    }
+
+    pub fn from_histogram(mut histogram: ConcurrentDoubleHistogramImpl<P>) -> Self {
+        // the first interval starts with the recorder
+        let meta_data = histogram.meta_data_mut();
+        if meta_data.start_timestamp.is_none() {
+            meta_data.set_start_now();
+        }
+        DoubleRecorderImpl {
+            recording_phaser: WriterReaderPhaser::new(),
+            active_histogram: AtomicPtr::new(Box::into_raw(Box::new(histogram))),
+        }
+    }
+
+    pub fn record_value(&self, value: f64) -> Result<(), RecordError> {
+        unsafe {
+            let _csg = self.recording_phaser.begin_writer_critical_section();
+            (*self.active_histogram.load(Ordering::Relaxed)).record_value(value)
+        }
+    }
+
+    pub fn record_value_with_count(&self, value: f64, count: u64) -> Result<(), RecordError> {
+        unsafe {
+            let _csg = self.recording_phaser.begin_writer_critical_section();
+            (*self.active_histogram.load(Ordering::Relaxed)).record_value_with_count(value, count)
+        }
+    }
+
+    pub fn record_value_with_expected_interval(&self, value: f64, expected_interval_between_value_samples: f64) -> Result<(), RecordError> {
+        unsafe {
+            let _csg = self.recording_phaser.begin_writer_critical_section();
+            (*self.active_histogram.load(Ordering::Relaxed))
+                .record_value_with_expected_interval(value, expected_interval_between_value_samples)
+        }
+    }
+
+    pub fn locking_sample<'a>(&'a self) -> DoubleLockingSample<'a, P> {
+        let pfg = self.recording_phaser.reader_lock();
+        let fresh_histogram = unsafe {
+            let active = &*self.active_histogram.load(Ordering::Relaxed);
+            let fresh = ConcurrentDoubleHistogramImpl::<P>::with_highest_to_lowest_value_ratio(
+                active.get_highest_to_lowest_value_ratio(),
+                active.get_number_of_significant_value_digits(),
+            ).unwrap();
+            fresh.set_auto_resize(active.is_auto_resize());
+            fresh
+        };
+        let histogram = self.perform_interval_sample(Box::new(fresh_histogram), &pfg);
+        DoubleLockingSample {
+            parent_recorder: self,
+            histogram,
+            guard: pfg,
+        }
+    }
+
+    fn perform_interval_sample<'a>(
+        &self,
+        mut inactive_histogram: Box<ConcurrentDoubleHistogramImpl<P>>,
+        flip_guard: &PhaseFlipGuard<'a>,
+    ) -> Box<ConcurrentDoubleHistogramImpl<P>> {
+        // writers can reach the histogram as soon as it is swapped in, so fill in its metadata before
+        let time_unit = unsafe { (*self.active_histogram.load(Ordering::Relaxed)).meta_data().time_unit };
+        let meta_data = inactive_histogram.meta_data_mut();
+        meta_data.set_start_now();
+        meta_data.time_unit = time_unit;
+        let active_histogram = self.active_histogram
+            .swap(Box::into_raw(inactive_histogram), Ordering::SeqCst);
+
+        flip_guard.flip();
+
+        let mut active_histogram = unsafe { Box::from_raw(active_histogram) };
+        active_histogram.meta_data_mut().set_end_now();
+        active_histogram
+    }
+}
+
+impl<'a, P: OverflowPolicy> DoubleLockingSample<'a, P> {
+    pub fn histogram(&self) -> &ConcurrentDoubleHistogramImpl<P> {
+        &self.histogram
+    }
+
+    /// Recycles this sample's histogram as the active one and samples again.
+    pub fn resample(self) -> Self {
+        let DoubleLockingSample { parent_recorder, histogram, guard } = self;
+        histogram.reset();
+        let histogram = parent_recorder.perform_interval_sample(histogram, &guard);
+        DoubleLockingSample {
+            parent_recorder,
+            histogram,
+            guard,
+        }
+    }
+}
+
+impl<P: OverflowPolicy> Drop for DoubleRecorderImpl<P> {
+    fn drop(&mut self) {
+        unsafe {
+            self.recording_phaser.reader_lock().flip();
+            mem::drop(Box::from_raw(self.active_histogram.load(Ordering::SeqCst)));
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::concurrent::latency_timer::{Clock, LatencyTimer, MonotonicClock};
+use crate::concurrent::locking_sample::LockingSample;
+use crate::concurrent::recordable_histogram::RecordableHistogram;
+use crate::concurrent::recorder::Recorder;
+use crate::core::*;
+use crate::core::util;
+use crate::iteration::*;
+use std::time::{Duration, Instant};
+
+/// A `Recorder` recording `Duration`s as integer counts of a fixed `TimeUnit`. Every interval
+/// histogram sampled from it carries the unit in its metadata.
+pub struct DurationRecorder<T: RecordableHistogram> {
+    recorder: Recorder<T>,
+}
+
+/// An interval sample of a `DurationRecorder`, answering queries in durations.
+pub struct DurationSample<'a, T: 'a + RecordableHistogram> {
+    sample: LockingSample<'a, 'a, T>,
+    time_unit: TimeUnit,
+}
+
+impl<T: RecordableHistogram> DurationRecorder<T> {
+    /// Wraps a recorder whose values are already in `time_unit`.
+    pub fn from_recorder(recorder: Recorder<T>, time_unit: TimeUnit) -> DurationRecorder<T> {
+        recorder.set_time_unit(time_unit);
+        DurationRecorder { recorder }
+    }
+
+    pub fn time_unit(&self) -> TimeUnit {
+        self.recorder.duration_unit()
+    }
+
+    pub fn recorder(&self) -> &Recorder<T> {
+        &self.recorder
+    }
+
+    pub fn record(&self, duration: Duration) -> Result<(), RecordError> {
+        self.recorder
+            .record_value(self.time_unit().to_value(duration))
+    }
+
+    pub fn record_with_count(&self, duration: Duration, count: u64) -> Result<(), RecordError> {
+        self.recorder
+            .record_value_with_count(self.time_unit().to_value(duration), count)
+    }
+
+    pub fn record_with_expected_interval(&self, duration: Duration, expected_interval: Duration) -> Result<(), RecordError> {
+        let time_unit = self.time_unit();
+        self.recorder.record_value_with_expected_interval(
+            time_unit.to_value(duration),
+            time_unit.to_value(expected_interval),
+        )
+    }
+
+    /// As `Recorder::start_timer`, which records in this recorder's unit.
+    pub fn start_timer(&self) -> LatencyTimer<'_, T, MonotonicClock> {
+        self.recorder.start_timer()
+    }
+
+    pub fn start_timer_with_clock<C: Clock>(&self, clock: C) -> LatencyTimer<'_, T, C> {
+        self.recorder.start_timer_with_clock(clock)
+    }
+
+    pub fn record_since(&self, start: Instant) -> Result<(), RecordError> {
+        self.recorder.record_since(start)
+    }
+
+    pub fn record_since_with_expected_interval(&self, start: Instant, expected_interval: Duration) -> Result<(), RecordError> {
+        self.recorder.record_since_with_expected_interval(start, expected_interval)
+    }
+
+    pub fn record_since_with_clock<C: Clock>(&self, clock: &C, start: Instant, expected_interval: Duration) -> Result<(), RecordError> {
+        self.recorder.record_since_with_clock(clock, start, expected_interval)
+    }
+
+    pub fn locking_sample<'a>(&'a self) -> DurationSample<'a, T> {
+        DurationSample {
+            sample: self.recorder.locking_sample(),
+            time_unit: self.time_unit(),
+        }
+    }
+}
+
+impl<'a, T: RecordableHistogram> DurationSample<'a, T> {
+    pub fn time_unit(&self) -> TimeUnit {
+        self.time_unit
+    }
+
+    pub fn sample(&self) -> &LockingSample<'a, 'a, T> {
+        &self.sample
+    }
+
+    pub fn into_sample(self) -> LockingSample<'a, 'a, T> {
+        self.sample
+    }
+
+    pub fn resample(self) -> Self {
+        DurationSample {
+            sample: self.sample.resample(),
+            time_unit: self.time_unit,
+        }
+    }
+
+    pub fn get_total_count(&self) -> u64 {
+        self.sample.sampled().get_total_count()
+    }
+
+    pub fn value_at_percentile(&self, percentile: f64) -> Duration {
+        self.time_unit
+            .to_duration(util::get_value_at_percentile(self.sample.sampled(), percentile))
+    }
+
+    pub fn values_at_percentiles(&self, percentiles: &[f64]) -> Vec<Duration> {
+        util::get_values_at_percentiles(self.sample.sampled(), percentiles)
+            .into_iter()
+            .map(|v| self.time_unit.to_duration(v))
+            .collect()
+    }
+
+    pub fn min(&self) -> Duration {
+        let histogram = self.sample.sampled();
+        let min = RecordedValuesIterator::new(histogram)
+            .next()
+            .map_or(0, |v| histogram.settings().lowest_equivalent_value(v.value_iterated_to));
+        self.time_unit.to_duration(min)
+    }
+
+    pub fn max(&self) -> Duration {
+        self.time_unit
+            .to_duration(self.sample.sampled().get_max_value())
+    }
+
+    pub fn mean(&self) -> Duration {
+        let mean = RecordedValuesIterator::get_mean_without_reset(&mut RecordedValuesIterator::new(self.sample.sampled()));
+        self.time_unit.to_duration_f64(mean)
+    }
+
+    pub fn recorded_values(&self) -> DurationValues<RecordedValuesIterator<'_, T>> {
+        DurationValues::new(RecordedValuesIterator::new(self.sample.sampled()), self.time_unit)
+    }
+
+    pub fn percentiles(&self, percentile_ticks_per_half_distance: u32) -> DurationValues<PercentileIterator<'_, T>> {
+        DurationValues::new(
+            PercentileIterator::new(self.sample.sampled(), percentile_ticks_per_half_distance),
+            self.time_unit,
+        )
+    }
+}
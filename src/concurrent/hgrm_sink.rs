@@ -0,0 +1,100 @@
+use crate::concurrent::reporter::IntervalSink;
+use crate::core::TimeUnit;
+use crate::st::Histogram;
+use std::io::{self, Write};
+
+/// Writes every interval histogram as a percentile distribution in the `.hgrm` format, with values
+/// converted from the histogram's time unit to `output_unit`. Histograms without a time unit are
+/// written unscaled.
+pub struct HgrmSink<W: Write + Send> {
+    writer: W,
+    output_unit: TimeUnit,
+    percentile_ticks_per_half_distance: u32,
+}
+
+impl HgrmSink<io::Stdout> {
+    pub fn stdout(output_unit: TimeUnit) -> HgrmSink<io::Stdout> {
+        HgrmSink::new(io::stdout(), output_unit)
+    }
+}
+
+impl<W: Write + Send> HgrmSink<W> {
+    pub fn new(writer: W, output_unit: TimeUnit) -> HgrmSink<W> {
+        HgrmSink {
+            writer,
+            output_unit,
+            percentile_ticks_per_half_distance: 5,
+        }
+    }
+
+    pub fn with_percentile_ticks_per_half_distance(mut self, percentile_ticks_per_half_distance: u32) -> HgrmSink<W> {
+        self.percentile_ticks_per_half_distance = percentile_ticks_per_half_distance;
+        self
+    }
+}
+
+impl<W: Write + Send> IntervalSink for HgrmSink<W> {
+    fn write_interval(&mut self, histogram: &Histogram<u64>) -> io::Result<()> {
+        let ratio = histogram
+            .meta_data
+            .time_unit
+            .map_or(1.0, |time_unit| time_unit.scaling_ratio_to(self.output_unit));
+        let digits = histogram.get_number_of_significant_value_digits() as usize;
+        let w = &mut self.writer;
+
+        if let Some(tag) = histogram.meta_data.tag.as_ref() {
+            writeln!(w, "#[Tag = {}]", tag)?;
+        }
+        write!(w, "{:>12} {:>14} {:>10} {:>14}\n\n", "Value", "Percentile", "TotalCount", "1/(1-Percentile)")?;
+        for value in histogram.percentiles(self.percentile_ticks_per_half_distance) {
+            let percentile = value.percentile_level_iterated_to / 100.0;
+            if value.percentile_level_iterated_to != 100.0 {
+                writeln!(
+                    w,
+                    "{:12.*} {:2.12} {:10} {:14.2}",
+                    digits,
+                    value.value_iterated_to as f64 / ratio,
+                    percentile,
+                    value.total_count_to_this_value,
+                    1.0 / (1.0 - percentile),
+                )?;
+            } else {
+                writeln!(
+                    w,
+                    "{:12.*} {:2.12} {:10}",
+                    digits,
+                    value.value_iterated_to as f64 / ratio,
+                    percentile,
+                    value.total_count_to_this_value,
+                )?;
+            }
+        }
+
+        let settings = histogram.settings();
+        writeln!(
+            w,
+            "#[Mean    = {:12.*}, StdDeviation   = {:12.*}]",
+            digits,
+            histogram.get_mean() / ratio,
+            digits,
+            histogram.get_std_deviation() / ratio,
+        )?;
+        writeln!(
+            w,
+            "#[Max     = {:12.*}, Total count    = {:12}]",
+            digits,
+            histogram.get_max_value() as f64 / ratio,
+            histogram.get_total_count(),
+        )?;
+        writeln!(
+            w,
+            "#[Buckets = {:12}, SubBuckets     = {:12}]",
+            settings.bucket_count, settings.sub_bucket_count,
+        )?;
+        writeln!(w)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
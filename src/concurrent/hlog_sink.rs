@@ -0,0 +1,73 @@
+use crate::concurrent::reporter::IntervalSink;
+use crate::core::TimeUnit;
+use crate::encoding;
+use crate::st::Histogram;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Writes interval histograms as a `.hlog` histogram log, readable by the HdrHistogram log tools.
+/// Interval timestamps are relative to the base time the log was started with. Interval maxima
+/// are converted from each histogram's time unit to `max_value_unit`, and written unscaled for
+/// histograms without one.
+pub struct HlogSink<W: Write + Send> {
+    writer: W,
+    base_time: SystemTime,
+    max_value_unit: TimeUnit,
+}
+
+impl HlogSink<BufWriter<File>> {
+    /// Creates or truncates the log at `path`, starting it now.
+    pub fn create<P: AsRef<Path>>(path: P, max_value_unit: TimeUnit) -> io::Result<HlogSink<BufWriter<File>>> {
+        HlogSink::new(BufWriter::new(File::create(path)?), SystemTime::now(), max_value_unit)
+    }
+}
+
+impl<W: Write + Send> HlogSink<W> {
+    /// Writes the log header.
+    pub fn new(mut writer: W, base_time: SystemTime, max_value_unit: TimeUnit) -> io::Result<HlogSink<W>> {
+        let base_secs = seconds_since(UNIX_EPOCH, base_time);
+        writeln!(writer, "#[Histogram log format version 1.3]")?;
+        writeln!(writer, "#[StartTime: {:.3} (seconds since epoch)]", base_secs)?;
+        writeln!(writer, "#[BaseTime: {:.3} (seconds since epoch)]", base_secs)?;
+        writeln!(writer, "\"StartTimestamp\",\"Interval_Length\",\"Interval_Max\",\"Interval_Compressed_Histogram\"")?;
+        Ok(HlogSink {
+            writer,
+            base_time,
+            max_value_unit,
+        })
+    }
+}
+
+impl<W: Write + Send> IntervalSink for HlogSink<W> {
+    fn write_interval(&mut self, histogram: &Histogram<u64>) -> io::Result<()> {
+        let meta_data = &histogram.meta_data;
+        let start = meta_data.start_timestamp.unwrap_or(self.base_time);
+        let end = meta_data.end_timestamp.unwrap_or(start);
+        let max_value_unit_ratio = meta_data
+            .time_unit
+            .map_or(1.0, |time_unit| time_unit.scaling_ratio_to(self.max_value_unit));
+        let encoded = encoding::encode_compressed_base64(histogram)
+            .map_err(|err| io::Error::other(format!("encoding the interval histogram failed: {:?}", err)))?;
+        if let Some(tag) = meta_data.tag.as_ref() {
+            write!(self.writer, "Tag={},", tag)?;
+        }
+        writeln!(
+            self.writer,
+            "{:.3},{:.3},{:.3},{}",
+            seconds_since(self.base_time, start),
+            seconds_since(start, end),
+            histogram.get_max_value() as f64 / max_value_unit_ratio,
+            encoded,
+        )
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+fn seconds_since(earlier: SystemTime, later: SystemTime) -> f64 {
+    later.duration_since(earlier).map(|d| d.as_secs_f64()).unwrap_or(0.0)
+}
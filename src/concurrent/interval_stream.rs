@@ -0,0 +1,133 @@
+use crate::concurrent::recordable_histogram::RecordableHistogram;
+use crate::concurrent::recorder::{PendingSample, Recorder};
+use crate::st::Histogram;
+use futures_core::Stream;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::time::{Duration, Instant};
+
+// how long to back off while another reader holds the recorder's reader lock
+const READER_LOCK_RETRY_DELAY: Duration = Duration::from_millis(1);
+
+/// Sleeps on behalf of an `IntervalStream`, so that the stream doesn't depend on a runtime.
+/// Closures returning a future, e.g. `|deadline| tokio::time::sleep_until(deadline.into())`,
+/// are timers.
+pub trait Timer {
+    type Sleep: Future<Output = ()>;
+
+    /// A future that completes once `deadline` has passed.
+    fn sleep_until(&mut self, deadline: Instant) -> Self::Sleep;
+}
+
+impl<F: FnMut(Instant) -> Fut, Fut: Future<Output = ()>> Timer for F {
+    type Sleep = Fut;
+
+    fn sleep_until(&mut self, deadline: Instant) -> Fut {
+        self(deadline)
+    }
+}
+
+/// A timer sleeping on the tokio runtime the stream is polled on.
+#[cfg(feature = "tokio")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TokioTimer;
+
+#[cfg(feature = "tokio")]
+impl Timer for TokioTimer {
+    type Sleep = tokio::time::Sleep;
+
+    fn sleep_until(&mut self, deadline: Instant) -> tokio::time::Sleep {
+        tokio::time::sleep_until(tokio::time::Instant::from_std(deadline))
+    }
+}
+
+/// A timer sleeping on the async-std runtime.
+#[cfg(feature = "async-std")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AsyncStdTimer;
+
+#[cfg(feature = "async-std")]
+impl Timer for AsyncStdTimer {
+    type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    fn sleep_until(&mut self, deadline: Instant) -> Self::Sleep {
+        Box::pin(async_std::task::sleep(deadline.saturating_duration_since(Instant::now())))
+    }
+}
+
+/// Samples a recorder every period, yielding each interval's values as an owned histogram. The
+/// stream never ends.
+///
+/// Unlike `PhaseFlipGuard::flip`, the stream never blocks the thread polling it: it waits for
+/// writers of the sampled phase by yielding back to the executor, and backs off on its timer
+/// while another reader holds the reader lock. Samples are scheduled off the first deadline
+/// rather than the previous sample, so time spent sampling doesn't accumulate; periods missed
+/// while the stream wasn't polled are skipped.
+pub struct IntervalStream<'a, T: RecordableHistogram, Tm: Timer> {
+    recorder: &'a Recorder<T>,
+    timer: Tm,
+    period: Duration,
+    next_tick: Instant,
+    sleep: Option<Pin<Box<Tm::Sleep>>>,
+    pending: Option<PendingSample<'a, T>>,
+}
+
+impl<'a, T: RecordableHistogram, Tm: Timer> IntervalStream<'a, T, Tm> {
+    /// Panics if `period` is zero.
+    pub fn new(recorder: &'a Recorder<T>, period: Duration, timer: Tm) -> IntervalStream<'a, T, Tm> {
+        assert!(!period.is_zero(), "interval stream period is zero");
+        IntervalStream {
+            recorder,
+            timer,
+            period,
+            next_tick: Instant::now() + period,
+            sleep: None,
+            pending: None,
+        }
+    }
+}
+
+// the timer is never pinned, and its sleep futures are pinned on the heap
+impl<'a, T: RecordableHistogram, Tm: Timer> Unpin for IntervalStream<'a, T, Tm> {}
+
+impl<'a, T: RecordableHistogram, Tm: Timer> Stream for IntervalStream<'a, T, Tm> {
+    type Item = Histogram<u64>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Histogram<u64>>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(pending) = this.pending.as_mut() {
+                if !pending.is_complete() {
+                    // writers leave their critical sections quickly, so try again on the next poll
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                let histogram = pending.interval_histogram();
+                this.pending = None;
+                let now = Instant::now();
+                if let Some(behind) = now.checked_duration_since(this.next_tick) {
+                    // the first tick after now, skipping every missed period at once
+                    let into_period = behind.as_nanos() % this.period.as_nanos();
+                    this.next_tick = now + (this.period - Duration::from_nanos(into_period as u64));
+                }
+                return Poll::Ready(Some(histogram));
+            }
+
+            let sleep = match this.sleep.as_mut() {
+                Some(sleep) => sleep,
+                None => this.sleep.insert(Box::pin(this.timer.sleep_until(this.next_tick))),
+            };
+            ready!(sleep.as_mut().poll(cx));
+            this.sleep = None;
+
+            match this.recorder.try_begin_interval_sample() {
+                Some(pending) => this.pending = Some(pending),
+                None => {
+                    let retry_at = Instant::now() + READER_LOCK_RETRY_DELAY;
+                    this.sleep = Some(Box::pin(this.timer.sleep_until(retry_at)));
+                }
+            }
+        }
+    }
+}
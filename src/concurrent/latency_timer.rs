@@ -0,0 +1,94 @@
+use crate::concurrent::recordable_histogram::RecordableHistogram;
+use crate::concurrent::recorder::Recorder;
+use crate::core::*;
+use std::time::{Duration, Instant};
+
+/// Source of the current time for latency timers, so tests can supply a deterministic one.
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// The default clock, reading `Instant::now()`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MonotonicClock;
+
+impl Clock for MonotonicClock {
+    #[inline]
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+impl<C: Clock> Clock for &C {
+    #[inline]
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+}
+
+/// Records the time elapsed since it was started into a recorder when dropped, or when `stop`
+/// is called. Elapsed time is converted to the timer's unit with saturation.
+pub struct LatencyTimer<'a, T: 'a + RecordableHistogram, C: Clock> {
+    recorder: &'a Recorder<T>,
+    clock: C,
+    start: Instant,
+    time_unit: TimeUnit,
+    expected_interval: u64,
+    armed: bool,
+}
+
+impl<'a, T: RecordableHistogram, C: Clock> LatencyTimer<'a, T, C> {
+    pub(in crate::concurrent) fn new(recorder: &'a Recorder<T>, clock: C, time_unit: TimeUnit) -> LatencyTimer<'a, T, C> {
+        let start = clock.now();
+        LatencyTimer {
+            recorder,
+            clock,
+            start,
+            time_unit,
+            expected_interval: 0,
+            armed: true,
+        }
+    }
+
+    /// Records through `record_value_with_expected_interval`, backfilling coordinated omission.
+    pub fn with_expected_interval(mut self, expected_interval: Duration) -> Self {
+        self.expected_interval = self.time_unit.to_value(expected_interval);
+        self
+    }
+
+    pub fn start(&self) -> Instant {
+        self.start
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.clock.now().saturating_duration_since(self.start)
+    }
+
+    /// Records the elapsed time now, returning it along with the outcome of the record.
+    pub fn stop(mut self) -> (Duration, Result<(), RecordError>) {
+        self.armed = false;
+        let elapsed = self.elapsed();
+        (elapsed, self.record(elapsed))
+    }
+
+    /// Drops the timer without recording anything.
+    pub fn cancel(mut self) {
+        self.armed = false;
+    }
+
+    fn record(&self, elapsed: Duration) -> Result<(), RecordError> {
+        self.recorder.record_value_with_expected_interval(
+            self.time_unit.to_value(elapsed),
+            self.expected_interval,
+        )
+    }
+}
+
+impl<'a, T: RecordableHistogram, C: Clock> Drop for LatencyTimer<'a, T, C> {
+    fn drop(&mut self) {
+        if self.armed {
+            // nowhere to report a failure from drop; use `stop` to observe it
+            let _ = self.record(self.elapsed());
+        }
+    }
+}
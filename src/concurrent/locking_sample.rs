@@ -2,6 +2,8 @@ use crate::concurrent::recordable_histogram::RecordableHistogram;
 use crate::concurrent::recorder::Recorder;
 use crate::concurrent::snapshot::Snapshot;
 use crate::concurrent::writer_reader_phaser::PhaseFlipGuard;
+use crate::core::*;
+use crate::st::SlidingWindowHistogram;
 use std::mem;
 use std::sync::atomic::{AtomicPtr, Ordering};
 
@@ -32,9 +34,22 @@ impl<'a, 'b: 'a, T: RecordableHistogram> LockingSample<'a, 'b, T> {
         }
     }
 
+    /// Takes the interval the recorder collected since the last tick via `resample` and pushes it
+    /// into `window`. Call this on every clock tick with the sample returned by the previous call;
+    /// whatever the initial `locking_sample()` captured is discarded.
+    pub fn resample_into_window(self, window: &mut SlidingWindowHistogram<u64>) -> Result<Self, RecordError> {
+        let sample = self.resample();
+        window.push_interval(&sample.histogram())?;
+        Ok(sample)
+    }
+
     pub fn histogram(&self) -> Snapshot<'_, T> {
         unsafe { Snapshot::new(&mut *self.histogram.load(Ordering::Relaxed)) }
     }
+
+    pub(in crate::concurrent) fn sampled(&self) -> &T {
+        unsafe { &*self.histogram.load(Ordering::Relaxed) }
+    }
 }
 
 impl<'a, 'b: 'a, T: RecordableHistogram> Drop for LockingSample<'a, 'b, T> {
@@ -1,17 +1,54 @@
 pub mod writer_reader_phaser;
+pub mod phased_double_buffer;
+pub mod atomic_counter;
 pub mod recorder;
+pub mod recorder_batch;
 pub mod resizable_histogram;
 pub mod static_histogram;
 pub mod double_histogram;
+pub mod double_recorder;
 pub(crate) mod inline_backing_array;
 pub mod recordable_histogram;
 pub mod snapshot;
 pub mod locking_sample;
 pub mod concurrent_util;
+pub mod duration_recorder;
+pub mod latency_timer;
+pub mod single_writer_recorder;
+pub mod sharded_recorder;
+pub mod recorder_registry;
+pub mod reporter;
+pub mod hgrm_sink;
+#[cfg(feature = "encoding-base64")]
+pub mod hlog_sink;
+#[cfg(feature = "async")]
+pub mod interval_stream;
 
 use self::snapshot::Snapshot;
-pub use self::recorder::Recorder;
-pub use self::resizable_histogram::ResizableHistogram;
-pub use self::static_histogram::StaticHistogram;
-pub use self::writer_reader_phaser::WriterReaderPhaser;
+pub use self::atomic_counter::AtomicCounter;
+pub use self::duration_recorder::{DurationRecorder, DurationSample};
+pub use self::latency_timer::{Clock, LatencyTimer, MonotonicClock};
+pub use self::recorder::{PendingSample, Recorder};
+pub use self::recorder_batch::RecorderBatch;
+pub use self::recorder_registry::{MetricKey, RecorderRegistry};
+pub use self::reporter::{IntervalSink, Reporter};
+pub use self::hgrm_sink::HgrmSink;
+#[cfg(feature = "encoding-base64")]
+pub use self::hlog_sink::HlogSink;
+#[cfg(feature = "async")]
+pub use self::interval_stream::{IntervalStream, Timer};
+#[cfg(feature = "tokio")]
+pub use self::interval_stream::TokioTimer;
+#[cfg(feature = "async-std")]
+pub use self::interval_stream::AsyncStdTimer;
+pub use self::sharded_recorder::ShardedRecorder;
+pub use self::single_writer_recorder::{
+    SaturatingSingleWriterDoubleRecorder, SingleWriterDoubleRecorder, SingleWriterDoubleRecorderImpl, SingleWriterRecorder,
+    SingleWriterSample, SingleWriterSampler,
+};
+pub use self::resizable_histogram::{ResizableHistogram, ResizableHistogram16, ResizableHistogram32, ResizableHistogramImpl};
+pub use self::static_histogram::{StaticHistogram, StaticHistogram16, StaticHistogram32, StaticHistogramImpl};
+pub use self::writer_reader_phaser::{FlipTimeout, WaitStrategy, WriterReaderPhaser};
+pub use self::phased_double_buffer::{PhasedDoubleBuffer, PhasedReader, PhasedWriteGuard};
 pub use self::double_histogram::{ConcurrentDoubleHistogram, ConcurrentDoubleHistogramImpl, SaturatingConcurrentDoubleHistogram};
+pub use self::double_recorder::{DoubleLockingSample, DoubleRecorder, DoubleRecorderImpl, SaturatingDoubleRecorder};
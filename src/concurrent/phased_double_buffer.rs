@@ -0,0 +1,117 @@
+use crate::concurrent::writer_reader_phaser::{PhaseFlipGuard, WaitStrategy, WriterCriticalSectionGuard, WriterReaderPhaser};
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+/// Two values of which writers update the active one through shared references, while a reader
+/// swaps them and gets exclusive access to the one writers just left, as `Recorder` does with its
+/// histograms. `T` has to handle concurrent updates through `&T` itself, e.g. with atomics.
+pub struct PhasedDoubleBuffer<T> {
+    phaser: WriterReaderPhaser,
+    active: AtomicPtr<T>,
+    // only touched under the reader lock
+    inactive: AtomicPtr<T>,
+    // owns both values, which the reader may move between threads
+    _marker: PhantomData<*mut T>,
+}
+
+unsafe impl<T: Send> Send for PhasedDoubleBuffer<T> {}
+unsafe impl<T: Send + Sync> Sync for PhasedDoubleBuffer<T> {}
+
+/// A writer's access to the active value, holding a writer critical section until dropped.
+pub struct PhasedWriteGuard<'a, T> {
+    value: &'a T,
+    _section: WriterCriticalSectionGuard<'a>,
+}
+
+/// Exclusive access to the inactive value, holding the reader lock until dropped.
+pub struct PhasedReader<'a, T> {
+    buffer: &'a PhasedDoubleBuffer<T>,
+    guard: PhaseFlipGuard<'a>,
+}
+
+impl<T> PhasedDoubleBuffer<T> {
+    /// `active` is the value writers start on, `inactive` the one the first swap activates.
+    pub fn new(active: T, inactive: T) -> PhasedDoubleBuffer<T> {
+        PhasedDoubleBuffer {
+            phaser: WriterReaderPhaser::new(),
+            active: AtomicPtr::new(Box::into_raw(Box::new(active))),
+            inactive: AtomicPtr::new(Box::into_raw(Box::new(inactive))),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Enters a writer critical section, giving access to the active value until the guard drops.
+    /// Guards should be short-lived, as a swap waits for every guard taken before it.
+    pub fn begin_write(&self) -> PhasedWriteGuard<'_, T> {
+        let section = self.phaser.begin_writer_critical_section();
+        PhasedWriteGuard {
+            value: unsafe { &*self.active.load(Ordering::Relaxed) },
+            _section: section,
+        }
+    }
+
+    pub fn write<R, F: FnOnce(&T) -> R>(&self, f: F) -> R {
+        f(&self.begin_write())
+    }
+
+    /// Takes the reader lock, blocking while another reader holds it.
+    pub fn reader(&self) -> PhasedReader<'_, T> {
+        PhasedReader {
+            buffer: self,
+            guard: self.phaser.reader_lock(),
+        }
+    }
+
+    /// Swaps the values and passes the one writers were using to `f`.
+    pub fn swap<R, F: FnOnce(&mut T) -> R>(&self, f: F) -> R {
+        f(self.reader().swap())
+    }
+}
+
+impl<T: Default> Default for PhasedDoubleBuffer<T> {
+    fn default() -> PhasedDoubleBuffer<T> {
+        PhasedDoubleBuffer::new(T::default(), T::default())
+    }
+}
+
+impl<T> Drop for PhasedDoubleBuffer<T> {
+    fn drop(&mut self) {
+        unsafe {
+            mem::drop(Box::from_raw(self.active.load(Ordering::SeqCst)));
+            mem::drop(Box::from_raw(self.inactive.load(Ordering::SeqCst)));
+        }
+    }
+}
+
+impl<'a, T> Deref for PhasedWriteGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<'a, T> PhasedReader<'a, T> {
+    /// The value writers aren't using, e.g. to reset it before it is swapped in.
+    pub fn inactive(&mut self) -> &mut T {
+        unsafe { &mut *self.buffer.inactive.load(Ordering::Relaxed) }
+    }
+
+    /// Activates the inactive value, waits for the writers of the previously active one and
+    /// returns it, now inactive.
+    pub fn swap(&mut self) -> &mut T {
+        self.swap_with_wait_strategy(WaitStrategy::Yield)
+    }
+
+    pub fn swap_with_wait_strategy(&mut self, strategy: WaitStrategy) -> &mut T {
+        let buffer = self.buffer;
+        let previously_active = buffer
+            .active
+            .swap(buffer.inactive.load(Ordering::Relaxed), Ordering::SeqCst);
+        buffer.inactive.store(previously_active, Ordering::Relaxed);
+        self.guard.flip_with_wait_strategy(strategy);
+        self.inactive()
+    }
+}
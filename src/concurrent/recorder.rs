@@ -1,11 +1,22 @@
+use crate::concurrent::latency_timer::{Clock, LatencyTimer, MonotonicClock};
 use crate::concurrent::locking_sample::LockingSample;
 use crate::concurrent::recordable_histogram::RecordableHistogram;
-use crate::concurrent::resizable_histogram::ResizableHistogram;
-use crate::concurrent::static_histogram::StaticHistogram;
-use crate::concurrent::writer_reader_phaser::{PhaseFlipGuard, WriterReaderPhaser};
+use crate::concurrent::recorder_batch::RecorderBatch;
+use crate::concurrent::resizable_histogram::{ResizableHistogram, ResizableHistogram16, ResizableHistogram32};
+use crate::concurrent::static_histogram::{StaticHistogram, StaticHistogram16, StaticHistogram32};
+use crate::concurrent::writer_reader_phaser::{FlipTimeout, PendingFlip, PhaseFlipGuard, WaitStrategy, WriterReaderPhaser};
+#[cfg(feature = "async")]
+use crate::concurrent::interval_stream::{IntervalStream, Timer};
 use crate::core::*;
+use crate::st::Histogram;
 use std::mem;
-use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicU8, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How many values `Recorder::record_values` records per writer critical section.
+pub const RECORD_VALUES_CHUNK_LENGTH: usize = 1024;
 
 static REPORTER_INSTANCE_SEQUENCER: AtomicUsize = AtomicUsize::new(0);
 fn get_instance_id() -> usize {
@@ -16,10 +27,18 @@ pub struct Recorder<T: RecordableHistogram> {
     pub instance_id: usize,
     recording_phaser: WriterReaderPhaser,
     active_histogram: AtomicPtr<T>,
+    // cleared histogram recycled by `sample_into`, null until the first call
+    inactive_histogram: AtomicPtr<T>,
+    // the unit of recorded values, stamped on each histogram as it is sampled; 0 while unset
+    time_unit: AtomicU8,
 }
 
 pub type StaticRecorder<const N: usize> = Recorder<StaticHistogram<N>>;
+pub type StaticRecorder32<const N: usize> = Recorder<StaticHistogram32<N>>;
+pub type StaticRecorder16<const N: usize> = Recorder<StaticHistogram16<N>>;
 pub type ResizableRecorder = Recorder<ResizableHistogram>;
+pub type ResizableRecorder32 = Recorder<ResizableHistogram32>;
+pub type ResizableRecorder16 = Recorder<ResizableHistogram16>;
 
 pub fn static_with_low_high_sigvdig<const N: usize>(
     lowest_discernible_value: u64,
@@ -46,12 +65,19 @@ pub fn resizable_with_low_high_sigvdig(
 }
 
 impl<T: RecordableHistogram> Recorder<T> {
-    pub fn from_histogram(histogram: T) -> Recorder<T> {
+    pub fn from_histogram(mut histogram: T) -> Recorder<T> {
+        // the first interval starts with the recorder
+        if histogram.meta_data().start_timestamp.is_none() {
+            histogram.meta_data_mut().set_start_now();
+        }
+        let time_unit = AtomicU8::new(encode_time_unit(histogram.meta_data().time_unit));
         let boxed_histo = Box::new(histogram);
         Recorder {
             instance_id: get_instance_id(),
             recording_phaser: WriterReaderPhaser::new(),
             active_histogram: AtomicPtr::new(Box::into_raw(boxed_histo)),
+            inactive_histogram: AtomicPtr::new(ptr::null_mut()),
+            time_unit,
         }
     }
     pub fn record_value(&self, value: u64) -> Result<(), RecordError> {
@@ -78,16 +104,7 @@ impl<T: RecordableHistogram> Recorder<T> {
         unsafe {
             let _csg = self.recording_phaser.begin_writer_critical_section();
             let active_histogram = &*self.active_histogram.load(Ordering::Relaxed);
-            active_histogram.record_value_with_count(value, count)?;
-            if expected_interval_between_value_samples != 0 && value > expected_interval_between_value_samples {
-                let mut missing_value = value - expected_interval_between_value_samples;
-                while missing_value >= expected_interval_between_value_samples {
-                    active_histogram.record_value_with_count(missing_value, count)?;
-                    missing_value -= expected_interval_between_value_samples;
-                }
-            }
-
-            Ok(())
+            record_corrected(active_histogram, value, count, expected_interval_between_value_samples)
         }
     }
 
@@ -96,6 +113,58 @@ impl<T: RecordableHistogram> Recorder<T> {
         self.record_value_with_count_and_expected_interval(value, 1, expected_interval_betwee_values)
     }
 
+    /// Records `values` in order, stopping at the first that fails. Values are recorded under one
+    /// writer critical section per `RECORD_VALUES_CHUNK_LENGTH` values, so that a sample taken
+    /// meanwhile doesn't wait for the whole slice.
+    pub fn record_values(&self, values: &[u64]) -> Result<(), RecordError> {
+        values
+            .chunks(RECORD_VALUES_CHUNK_LENGTH)
+            .try_for_each(|chunk| self.begin_batch().record_values(chunk))
+    }
+
+    /// Opens a batch recording into the active histogram until it is dropped, entering the
+    /// writer critical section once rather than per value.
+    pub fn begin_batch(&self) -> RecorderBatch<'_, T> {
+        let section = self.recording_phaser.begin_writer_critical_section();
+        let active_histogram = unsafe { &*self.active_histogram.load(Ordering::Relaxed) };
+        RecorderBatch::new(active_histogram, section)
+    }
+
+    pub fn batch<R, F: FnOnce(&RecorderBatch<'_, T>) -> R>(&self, f: F) -> R {
+        f(&self.begin_batch())
+    }
+
+    /// Starts a timer that records the elapsed time into this recorder when dropped, in the unit
+    /// the recorder has when the timer starts.
+    pub fn start_timer(&self) -> LatencyTimer<'_, T, MonotonicClock> {
+        self.start_timer_with_clock(MonotonicClock)
+    }
+
+    pub fn start_timer_with_clock<C: Clock>(&self, clock: C) -> LatencyTimer<'_, T, C> {
+        LatencyTimer::new(self, clock, self.duration_unit())
+    }
+
+    /// Records the time elapsed since `start` in the recorder's unit, saturating rather than
+    /// truncating.
+    pub fn record_since(&self, start: Instant) -> Result<(), RecordError> {
+        self.record_since_with_clock(&MonotonicClock, start, Duration::ZERO)
+    }
+
+    pub fn record_since_with_expected_interval(&self, start: Instant, expected_interval: Duration) -> Result<(), RecordError> {
+        self.record_since_with_clock(&MonotonicClock, start, expected_interval)
+    }
+
+    /// As `record_since_with_expected_interval`, reading the current time from `clock`. A zero
+    /// expected interval disables coordinated omission correction.
+    pub fn record_since_with_clock<C: Clock>(&self, clock: &C, start: Instant, expected_interval: Duration) -> Result<(), RecordError> {
+        let elapsed = clock.now().saturating_duration_since(start);
+        let time_unit = self.duration_unit();
+        self.record_value_with_expected_interval(
+            time_unit.to_value(elapsed),
+            time_unit.to_value(expected_interval),
+        )
+    }
+
     pub fn locking_sample<'a>(&'a self) -> LockingSample<'a, 'a, T> {
         let pfg = self.recording_phaser.reader_lock();
         let settings = unsafe { (&*self.active_histogram.load(Ordering::Relaxed)).settings() };
@@ -104,20 +173,208 @@ impl<T: RecordableHistogram> Recorder<T> {
         LockingSample::new(&self, sample, pfg)
     }
 
-    pub(in crate::concurrent) fn perform_interval_sample<'a>(&self, inactive_histogram: *mut T, flip_guard: &PhaseFlipGuard<'a>) -> *mut T {
-        let active_histogram = self.active_histogram
-            .swap(inactive_histogram, Ordering::SeqCst);
+    /// Samples the values recorded since the previous sample into an owned histogram that can be
+    /// sent to another thread. The reader lock is released before this returns.
+    pub fn interval_histogram(&self) -> Histogram<u64> {
+        let pfg = self.recording_phaser.reader_lock();
+        let sampled = self.perform_interval_sample(self.take_inactive_histogram(), &pfg);
+        unsafe {
+            let interval = Histogram::copy_of(&*sampled);
+            (*sampled).clear_counts();
+            self.inactive_histogram.store(sampled, Ordering::Relaxed);
+            interval
+        }
+    }
+
+    /// Moves the values recorded since the previous sample into `target`, discarding its contents.
+    /// Only the boxes are swapped, never the counts. `target`'s storage is kept as the next
+    /// histogram to swap in, so a caller alternating between the recorder and one histogram of
+    /// the same configuration samples without allocating after the first call.
+    pub fn sample_into(&self, target: &mut Box<T>) {
+        let pfg = self.recording_phaser.reader_lock();
+        let sampled = self.perform_interval_sample(self.take_inactive_histogram(), &pfg);
         unsafe {
-            (*self.active_histogram.load(Ordering::Relaxed))
-                .meta_data_mut()
-                .set_start_now()
-        };
+            let previous_target = mem::replace(target, Box::from_raw(sampled));
+            let active_settings = (&*self.active_histogram.load(Ordering::Relaxed)).settings();
+            if previous_target.settings().equals(active_settings) {
+                previous_target.clear_counts();
+                self.inactive_histogram.store(Box::into_raw(previous_target), Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// A stream yielding the values recorded in each `period` as an owned interval histogram,
+    /// waiting on `timer` between samples instead of blocking the executor. The first interval
+    /// starts with the previous sample, or with the recorder if there was none.
+    #[cfg(feature = "async")]
+    pub fn interval_stream<Tm: Timer>(&self, period: Duration, timer: Tm) -> IntervalStream<'_, T, Tm> {
+        IntervalStream::new(self, period, timer)
+    }
+
+    /// Marks the values recorded from now on as durations in `time_unit`. Every interval
+    /// histogram sampled from this recorder carries the unit in its metadata, and timers record
+    /// in it.
+    pub fn set_time_unit(&self, time_unit: TimeUnit) {
+        self.time_unit.store(encode_time_unit(Some(time_unit)), Ordering::Relaxed);
+    }
+
+    pub fn time_unit(&self) -> Option<TimeUnit> {
+        decode_time_unit(self.time_unit.load(Ordering::Relaxed))
+    }
+
+    // the unit elapsed times are recorded in, nanoseconds unless one is set
+    pub(in crate::concurrent) fn duration_unit(&self) -> TimeUnit {
+        self.time_unit().unwrap_or(TimeUnit::Nanoseconds)
+    }
+
+    // Requires the reader lock.
+    fn take_inactive_histogram(&self) -> *mut T {
+        let inactive_histogram = self.inactive_histogram.swap(ptr::null_mut(), Ordering::Relaxed);
+        if inactive_histogram.is_null() {
+            let settings = unsafe { (&*self.active_histogram.load(Ordering::Relaxed)).settings() };
+            Box::into_raw(Box::new(T::fresh(settings).unwrap()))
+        } else {
+            inactive_histogram
+        }
+    }
+
+    pub(in crate::concurrent) fn perform_interval_sample<'a>(&self, inactive_histogram: *mut T, flip_guard: &PhaseFlipGuard<'a>) -> *mut T {
+        let active_histogram = self.swap_active_histogram(inactive_histogram);
 
         flip_guard.flip();
 
-        unsafe { (*active_histogram).meta_data_mut().set_end_now() };
+        self.end_interval(active_histogram);
         active_histogram
     }
+
+    // Requires the flip that retired `sampled` to be complete, so no writer can still reach it.
+    fn end_interval(&self, sampled: *mut T) {
+        let meta_data = unsafe { (*sampled).meta_data_mut() };
+        meta_data.set_end_now();
+        meta_data.time_unit = self.time_unit();
+    }
+
+    /// Starts sampling without waiting for the writers still recording into the sampled
+    /// histogram, so that a caller can bound the wait with `PendingSample::wait_until`. The reader
+    /// lock is held until the returned sample is dropped.
+    pub fn begin_interval_sample(&self) -> PendingSample<'_, T> {
+        self.begin_sample_with_guard(self.recording_phaser.reader_lock())
+    }
+
+    /// As `begin_interval_sample`, returning `None` instead of blocking while another reader holds
+    /// the reader lock.
+    #[cfg(feature = "async")]
+    pub(in crate::concurrent) fn try_begin_interval_sample(&self) -> Option<PendingSample<'_, T>> {
+        let guard = self.recording_phaser.try_reader_lock()?;
+        Some(self.begin_sample_with_guard(guard))
+    }
+
+    fn begin_sample_with_guard<'a>(&'a self, guard: PhaseFlipGuard<'a>) -> PendingSample<'a, T> {
+        let sampled = self.swap_active_histogram(self.take_inactive_histogram());
+        let flipped_at = Instant::now();
+        let flip = guard.begin_flip();
+        PendingSample {
+            recorder: self,
+            guard,
+            flip,
+            flipped_at,
+            sampled,
+        }
+    }
+
+    // Requires the reader lock, and a flip before the returned histogram is read.
+    fn swap_active_histogram(&self, inactive_histogram: *mut T) -> *mut T {
+        // writers can reach the histogram as soon as it is swapped in, so stamp it before
+        unsafe { (*inactive_histogram).meta_data_mut().set_start_now() };
+        self.active_histogram.swap(inactive_histogram, Ordering::SeqCst)
+    }
+}
+
+/// A sample whose flip may still be waiting for writers. Dropping it waits for the flip to
+/// complete and recycles the sampled histogram as the recorder's next inactive histogram.
+pub struct PendingSample<'a, T: RecordableHistogram> {
+    recorder: &'a Recorder<T>,
+    guard: PhaseFlipGuard<'a>,
+    flip: PendingFlip,
+    flipped_at: Instant,
+    sampled: *mut T,
+}
+
+// the sampled histogram is owned by the sample until it is handed back to the recorder
+unsafe impl<'a, T: RecordableHistogram + Send> Send for PendingSample<'a, T> {}
+
+impl<'a, T: RecordableHistogram> PendingSample<'a, T> {
+    /// Whether every writer recording into the sampled histogram has left it.
+    pub fn is_complete(&self) -> bool {
+        self.guard.is_flip_complete(&self.flip)
+    }
+
+    /// Waits with `strategy` for the writers recording into the sampled histogram, giving up at
+    /// `deadline`. On success, returns how long they took to catch up since the sample began. On
+    /// timeout the sample stays pending and can be waited for again.
+    pub fn wait_until(&self, deadline: Instant, strategy: WaitStrategy) -> Result<Duration, FlipTimeout> {
+        self.guard.wait_for_flip_until(&self.flip, deadline, strategy)?;
+        Ok(self.flipped_at.elapsed())
+    }
+
+    /// Copies out the sampled histogram, ending its interval now. Panics if the sample isn't
+    /// complete.
+    pub fn interval_histogram(&mut self) -> Histogram<u64> {
+        assert!(self.is_complete(), "writers are still recording into the sampled histogram");
+        self.recorder.end_interval(self.sampled);
+        unsafe { Histogram::copy_of(&*self.sampled) }
+    }
+}
+
+impl<'a, T: RecordableHistogram> Drop for PendingSample<'a, T> {
+    fn drop(&mut self) {
+        while !self.is_complete() {
+            thread::yield_now();
+        }
+        unsafe {
+            (*self.sampled).clear_counts();
+            self.recorder.inactive_histogram.store(self.sampled, Ordering::Relaxed);
+        }
+    }
+}
+
+// 0 while no time unit is set
+fn encode_time_unit(time_unit: Option<TimeUnit>) -> u8 {
+    match time_unit {
+        None => 0,
+        Some(TimeUnit::Nanoseconds) => 1,
+        Some(TimeUnit::Microseconds) => 2,
+        Some(TimeUnit::Milliseconds) => 3,
+    }
+}
+
+fn decode_time_unit(encoded: u8) -> Option<TimeUnit> {
+    match encoded {
+        1 => Some(TimeUnit::Nanoseconds),
+        2 => Some(TimeUnit::Microseconds),
+        3 => Some(TimeUnit::Milliseconds),
+        _ => None,
+    }
+}
+
+// records `value`, plus the values a coordinated omission would have hidden
+#[inline]
+pub(in crate::concurrent) fn record_corrected<T: RecordableHistogram>(
+    histogram: &T,
+    value: u64,
+    count: u64,
+    expected_interval_between_value_samples: u64,
+) -> Result<(), RecordError> {
+    histogram.record_value_with_count(value, count)?;
+    if expected_interval_between_value_samples != 0 && value > expected_interval_between_value_samples {
+        let mut missing_value = value - expected_interval_between_value_samples;
+        while missing_value >= expected_interval_between_value_samples {
+            histogram.record_value_with_count(missing_value, count)?;
+            missing_value -= expected_interval_between_value_samples;
+        }
+    }
+
+    Ok(())
 }
 
 impl<T: RecordableHistogram> Drop for Recorder<T> {
@@ -125,6 +382,10 @@ impl<T: RecordableHistogram> Drop for Recorder<T> {
         unsafe {
             self.recording_phaser.reader_lock().flip();
             mem::drop(Box::from_raw(self.active_histogram.load(Ordering::SeqCst)));
+            let inactive_histogram = self.inactive_histogram.load(Ordering::SeqCst);
+            if !inactive_histogram.is_null() {
+                mem::drop(Box::from_raw(inactive_histogram));
+            }
         }
     }
 }
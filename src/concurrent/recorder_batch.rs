@@ -0,0 +1,40 @@
+use crate::concurrent::recordable_histogram::RecordableHistogram;
+use crate::concurrent::recorder;
+use crate::concurrent::writer_reader_phaser::WriterCriticalSectionGuard;
+use crate::core::*;
+
+/// Records into a recorder's active histogram under a single writer critical section, which is
+/// held until the batch is dropped. Samples wait for open batches, so batches should be short.
+pub struct RecorderBatch<'a, T: 'a + RecordableHistogram> {
+    histogram: &'a T,
+    _section: WriterCriticalSectionGuard<'a>,
+}
+
+impl<'a, T: RecordableHistogram> RecorderBatch<'a, T> {
+    pub(in crate::concurrent) fn new(histogram: &'a T, section: WriterCriticalSectionGuard<'a>) -> RecorderBatch<'a, T> {
+        RecorderBatch {
+            histogram,
+            _section: section,
+        }
+    }
+
+    #[inline]
+    pub fn record_value(&self, value: u64) -> Result<(), RecordError> {
+        self.histogram.record_value(value)
+    }
+
+    #[inline]
+    pub fn record_value_with_count(&self, value: u64, count: u64) -> Result<(), RecordError> {
+        self.histogram.record_value_with_count(value, count)
+    }
+
+    #[inline]
+    pub fn record_value_with_expected_interval(&self, value: u64, expected_interval_between_value_samples: u64) -> Result<(), RecordError> {
+        recorder::record_corrected(self.histogram, value, 1, expected_interval_between_value_samples)
+    }
+
+    /// Records every value in order, stopping at the first that fails.
+    pub fn record_values(&self, values: &[u64]) -> Result<(), RecordError> {
+        values.iter().try_for_each(|&value| self.histogram.record_value(value))
+    }
+}
@@ -0,0 +1,147 @@
+use crate::concurrent::recorder::{self, ResizableRecorder};
+use crate::core::*;
+use crate::st::Histogram;
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// A metric name and its label set. Labels are kept sorted by key, so the order they are given in
+/// doesn't matter.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MetricKey {
+    name: String,
+    labels: Vec<(String, String)>,
+}
+
+impl MetricKey {
+    pub fn new(name: &str, labels: &[(&str, &str)]) -> Result<MetricKey, TagError> {
+        check_tag_part(name)?;
+        let mut owned_labels = Vec::with_capacity(labels.len());
+        for &(key, value) in labels {
+            check_tag_part(key)?;
+            if let Some(c) = value.chars().find(|&c| !is_valid_tag_char(c)) {
+                return Err(TagError::InvalidCharacter(c));
+            }
+            owned_labels.push((key.to_owned(), value.to_owned()));
+        }
+        owned_labels.sort();
+        Ok(MetricKey {
+            name: name.to_owned(),
+            labels: owned_labels,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn labels(&self) -> &[(String, String)] {
+        &self.labels
+    }
+
+    /// The key as a log tag, `name;key=value;...`.
+    pub fn tag_string(&self) -> String {
+        let mut tag = self.name.clone();
+        for (key, value) in self.labels.iter() {
+            tag.push(';');
+            tag.push_str(key);
+            tag.push('=');
+            tag.push_str(value);
+        }
+        tag
+    }
+}
+
+// log tags can't hold separators or whitespace, and ';' and '=' delimit the labels
+fn is_valid_tag_char(c: char) -> bool {
+    !(c.is_whitespace() || c == ',' || c == ';' || c == '=')
+}
+
+fn check_tag_part(part: &str) -> Result<(), TagError> {
+    if part.is_empty() {
+        return Err(TagError::Empty);
+    }
+    match part.chars().find(|&c| !is_valid_tag_char(c)) {
+        Some(c) => Err(TagError::InvalidCharacter(c)),
+        None => Ok(()),
+    }
+}
+
+/// Recorders keyed by name and label set, created on first use with the registry's settings.
+///
+/// Looking a recorder up allocates its key, so hot paths should hold on to the returned `Arc`.
+pub struct RecorderRegistry {
+    lowest_discernible_value: u64,
+    highest_trackable_value: u64,
+    significant_value_digits: u8,
+    recorders: RwLock<BTreeMap<MetricKey, Arc<ResizableRecorder>>>,
+}
+
+impl RecorderRegistry {
+    pub fn with_low_high_sigvdig(
+        lowest_discernible_value: u64,
+        highest_trackable_value: u64,
+        significant_value_digits: u8,
+    ) -> Result<RecorderRegistry, CreationError> {
+        // validates the settings once, so that creating recorders later can't fail
+        HistogramSettings::new(
+            lowest_discernible_value,
+            highest_trackable_value,
+            significant_value_digits,
+        )?;
+        Ok(RecorderRegistry {
+            lowest_discernible_value,
+            highest_trackable_value,
+            significant_value_digits,
+            recorders: RwLock::new(BTreeMap::new()),
+        })
+    }
+
+    /// The recorder for `name` and `labels`, created if this is the first time they are used.
+    pub fn recorder(&self, name: &str, labels: &[(&str, &str)]) -> Result<Arc<ResizableRecorder>, TagError> {
+        let key = MetricKey::new(name, labels)?;
+        if let Some(recorder) = self.recorders.read().get(&key) {
+            return Ok(recorder.clone());
+        }
+        let mut recorders = self.recorders.write();
+        let recorder = recorders.entry(key).or_insert_with(|| {
+            Arc::new(
+                recorder::resizable_with_low_high_sigvdig(
+                    self.lowest_discernible_value,
+                    self.highest_trackable_value,
+                    self.significant_value_digits,
+                ).unwrap(),
+            )
+        });
+        Ok(recorder.clone())
+    }
+
+    /// Removes the recorder for `name` and `labels`. Values recorded since the last sample are lost,
+    /// as are any recorded later through an `Arc` still held elsewhere.
+    pub fn remove(&self, name: &str, labels: &[(&str, &str)]) -> Option<Arc<ResizableRecorder>> {
+        let key = MetricKey::new(name, labels).ok()?;
+        self.recorders.write().remove(&key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.recorders.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recorders.read().is_empty()
+    }
+
+    /// Takes the interval histogram of every recorder, ordered by key, each tagged with its key's
+    /// `tag_string`.
+    pub fn sample(&self) -> Vec<(MetricKey, Histogram<u64>)> {
+        self.recorders
+            .read()
+            .iter()
+            .map(|(key, recorder)| {
+                let mut histogram = recorder.interval_histogram();
+                histogram.meta_data.set_tag_string(key.tag_string());
+                (key.clone(), histogram)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,147 @@
+use crate::concurrent::recordable_histogram::RecordableHistogram;
+use crate::concurrent::recorder::{PendingSample, Recorder};
+use crate::concurrent::writer_reader_phaser::WaitStrategy;
+use crate::st::Histogram;
+use std::io;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Receives the interval histograms a `Reporter` samples.
+pub trait IntervalSink: Send {
+    /// Called with every recorder's interval histogram, tagged with the recorder's tag if it has one.
+    fn write_interval(&mut self, histogram: &Histogram<u64>) -> io::Result<()>;
+
+    /// Called after each interval histogram with how long the recorder's writers took to leave
+    /// it once it was sampled.
+    fn write_flip_latency(&mut self, _tag: Option<&str>, _latency: Duration) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Called instead of `write_interval` when writers were still recording into a recorder's
+    /// sampled histogram a full interval after it was sampled. The histogram is written on a
+    /// later tick, once they have left it.
+    fn write_flip_timeout(&mut self, _tag: Option<&str>, _writers_in_flight: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Called once all of an interval's histograms are written.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<F: FnMut(&Histogram<u64>) -> io::Result<()> + Send> IntervalSink for F {
+    fn write_interval(&mut self, histogram: &Histogram<u64>) -> io::Result<()> {
+        self(histogram)
+    }
+}
+
+/// Samples a set of recorders at a fixed interval on its own thread and passes the interval
+/// histograms to its sinks. Dropping the reporter stops the thread after it reports a final,
+/// shorter interval.
+///
+/// Ticks are scheduled off the start time rather than the previous tick, so time spent sampling
+/// doesn't accumulate; ticks missed while a sink blocks are skipped. Writers get one interval to
+/// leave a sampled histogram; a recorder whose writers are stuck is reported to the sinks with
+/// `write_flip_timeout` rather than hanging the reporter, and is not sampled again until they
+/// leave. Shutting down waits for them.
+pub struct Reporter {
+    shutdown: Option<Sender<()>>,
+    thread: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl Reporter {
+    pub fn start<T: RecordableHistogram + Send + 'static>(
+        interval: Duration,
+        recorders: Vec<(Option<String>, Arc<Recorder<T>>)>,
+        sinks: Vec<Box<dyn IntervalSink>>,
+    ) -> io::Result<Reporter> {
+        if interval.is_zero() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "reporting interval is zero"));
+        }
+        let (shutdown, shutdown_signal) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("hdrhistogram-reporter".to_owned())
+            .spawn(move || run(interval, recorders, sinks, shutdown_signal))?;
+        Ok(Reporter {
+            shutdown: Some(shutdown),
+            thread: Some(thread),
+        })
+    }
+
+    /// Stops the reporter after a final interval, returning the first error a sink ran into.
+    pub fn shutdown(mut self) -> io::Result<()> {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> io::Result<()> {
+        // disconnecting wakes the thread up
+        self.shutdown.take();
+        match self.thread.take() {
+            Some(thread) => thread
+                .join()
+                .unwrap_or_else(|_| Err(io::Error::other("reporter thread panicked"))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Reporter {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+fn run<T: RecordableHistogram>(
+    interval: Duration,
+    recorders: Vec<(Option<String>, Arc<Recorder<T>>)>,
+    mut sinks: Vec<Box<dyn IntervalSink>>,
+    shutdown_signal: Receiver<()>,
+) -> io::Result<()> {
+    // samples whose writers didn't catch up by their deadline, carried over to the next tick
+    let mut pending: Vec<Option<PendingSample<'_, T>>> = recorders.iter().map(|_| None).collect();
+    let mut result = Ok(());
+    let mut next_tick = Instant::now() + interval;
+    loop {
+        let timeout = next_tick.saturating_duration_since(Instant::now());
+        let stopping = !matches!(shutdown_signal.recv_timeout(timeout), Err(RecvTimeoutError::Timeout));
+
+        let deadline = Instant::now() + interval;
+        for ((tag, recorder), slot) in recorders.iter().zip(pending.iter_mut()) {
+            let sample = slot.get_or_insert_with(|| recorder.begin_interval_sample());
+            let tag = tag.as_deref();
+            match sample.wait_until(deadline, WaitStrategy::Park) {
+                Ok(latency) => {
+                    let mut histogram = sample.interval_histogram();
+                    if let Some(tag) = tag {
+                        histogram.meta_data.set_tag_string(tag.to_owned());
+                    }
+                    for sink in sinks.iter_mut() {
+                        result = result.and(sink.write_interval(&histogram));
+                        result = result.and(sink.write_flip_latency(tag, latency));
+                    }
+                    // recycles the sampled histogram
+                    *slot = None;
+                }
+                Err(timeout) => {
+                    for sink in sinks.iter_mut() {
+                        result = result.and(sink.write_flip_timeout(tag, timeout.writers_in_flight));
+                    }
+                }
+            }
+        }
+        for sink in sinks.iter_mut() {
+            result = result.and(sink.flush());
+        }
+
+        if stopping {
+            return result;
+        }
+        let now = Instant::now();
+        while next_tick <= now {
+            next_tick += interval;
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use crate::concurrent::{concurrent_util, Snapshot, WriterReaderPhaser};
+use crate::concurrent::concurrent_util::{self, CachePadded};
+use crate::concurrent::{AtomicCounter, Snapshot, WriterReaderPhaser};
 use crate::concurrent::inline_backing_array::InlineBackingArray;
 use crate::concurrent::recordable_histogram::RecordableHistogram;
 use crate::core::*;
@@ -6,22 +7,25 @@ use crate::core::constants::*;
 use crate::iteration::RecordedValuesIterator;
 use std::{mem, ptr};
 use std::cell::UnsafeCell;
-use std::sync::atomic::{AtomicPtr, AtomicU64};
+use std::sync::atomic::{AtomicPtr, AtomicU16, AtomicU32, AtomicU64};
 use std::sync::atomic::Ordering;
 
 #[repr(C)]
-pub struct ResizableHistogram {
+pub struct ResizableHistogramImpl<C: AtomicCounter> {
     meta_data: HistogramMetaData,
     settings: UnsafeCell<HistogramSettings>,
     wrp: WriterReaderPhaser,
-    raw_max_value: AtomicU64,
-    raw_min_non_zero_value: AtomicU64,
-    total_count: AtomicU64,
-    pub(in crate::concurrent) active_counts: AtomicPtr<InlineBackingArray<AtomicU64>>,
-    inactive_counts: AtomicPtr<InlineBackingArray<AtomicU64>>,
+    raw_max_value: CachePadded<AtomicU64>,
+    raw_min_non_zero_value: CachePadded<AtomicU64>,
+    pub(in crate::concurrent) active_counts: AtomicPtr<InlineBackingArray<C>>,
+    inactive_counts: AtomicPtr<InlineBackingArray<C>>,
 }
 
-impl Drop for ResizableHistogram {
+pub type ResizableHistogram = ResizableHistogramImpl<AtomicU64>;
+pub type ResizableHistogram32 = ResizableHistogramImpl<AtomicU32>;
+pub type ResizableHistogram16 = ResizableHistogramImpl<AtomicU16>;
+
+impl<C: AtomicCounter> Drop for ResizableHistogramImpl<C> {
     fn drop(&mut self) {
         unsafe {
             self.wrp.reader_lock().flip();
@@ -31,24 +35,24 @@ impl Drop for ResizableHistogram {
     }
 }
 
-unsafe impl Send for ResizableHistogram {}
-unsafe impl Sync for ResizableHistogram {}
+unsafe impl<C: AtomicCounter> Send for ResizableHistogramImpl<C> {}
+unsafe impl<C: AtomicCounter> Sync for ResizableHistogramImpl<C> {}
 
-impl ResizableHistogram {
-    pub fn new(significant_value_digits: u8) -> Result<ResizableHistogram, CreationError> {
-        ResizableHistogram::with_sigvdig(significant_value_digits)
+impl<C: AtomicCounter> ResizableHistogramImpl<C> {
+    pub fn new(significant_value_digits: u8) -> Result<ResizableHistogramImpl<C>, CreationError> {
+        ResizableHistogramImpl::<C>::with_sigvdig(significant_value_digits)
     }
-    pub fn with_sigvdig(significant_value_digits: u8) -> Result<ResizableHistogram, CreationError> {
-        ResizableHistogram::with_high_sigvdig(2, significant_value_digits)
+    pub fn with_sigvdig(significant_value_digits: u8) -> Result<ResizableHistogramImpl<C>, CreationError> {
+        ResizableHistogramImpl::<C>::with_high_sigvdig(2, significant_value_digits)
     }
-    pub fn with_high_sigvdig(highest_trackable_value: u64, significant_value_digits: u8) -> Result<ResizableHistogram, CreationError> {
-        ResizableHistogram::with_low_high_sigvdig(1, highest_trackable_value, significant_value_digits)
+    pub fn with_high_sigvdig(highest_trackable_value: u64, significant_value_digits: u8) -> Result<ResizableHistogramImpl<C>, CreationError> {
+        ResizableHistogramImpl::<C>::with_low_high_sigvdig(1, highest_trackable_value, significant_value_digits)
     }
     pub fn with_low_high_sigvdig(
         lowest_discernible_value: u64,
         highest_trackable_value: u64,
         significant_value_digits: u8,
-    ) -> Result<ResizableHistogram, CreationError> {
+    ) -> Result<ResizableHistogramImpl<C>, CreationError> {
         let mut settings = HistogramSettings::new(
             lowest_discernible_value,
             highest_trackable_value,
@@ -58,13 +62,12 @@ impl ResizableHistogram {
         unsafe {
             let active_array_ptr = InlineBackingArray::new(settings.counts_array_length);
             let inactive_array_ptr = InlineBackingArray::new(settings.counts_array_length);
-            Ok(ResizableHistogram {
+            Ok(ResizableHistogramImpl {
                 meta_data: HistogramMetaData::new(),
                 wrp: WriterReaderPhaser::new(),
                 settings: UnsafeCell::new(settings),
-                raw_max_value: AtomicU64::new(ORIGINAL_MAX),
-                raw_min_non_zero_value: AtomicU64::new(ORIGINAL_MIN),
-                total_count: AtomicU64::new(0),
+                raw_max_value: CachePadded(AtomicU64::new(ORIGINAL_MAX)),
+                raw_min_non_zero_value: CachePadded(AtomicU64::new(ORIGINAL_MIN)),
                 active_counts: AtomicPtr::new(active_array_ptr),
                 inactive_counts: AtomicPtr::new(inactive_array_ptr),
             })
@@ -122,6 +125,9 @@ impl ResizableHistogram {
             if idx >= settings.counts_array_length {
                 return Err(RecordError::ValueOutOfRangeResizeDisabled);
             }
+            // min and max first, so that a total derived from the counts never covers a value
+            // they don't
+            self.update_min_and_max(value);
             {
                 let _csg = self.wrp.begin_writer_critical_section();
                 let active_counts = &*self.active_counts.load(Ordering::Relaxed);
@@ -130,12 +136,8 @@ impl ResizableHistogram {
                     active_counts.normalizing_index_offset(),
                     active_counts.length(),
                 );
-                let c = active_counts.get_unchecked(normalized_index);
-                c.fetch_add(count, Ordering::Relaxed);
+                concurrent_util::add_count(active_counts.get_unchecked(normalized_index), count)
             }
-            self.update_min_and_max(value);
-            self.total_count.fetch_add(count, Ordering::Relaxed);
-            Ok(())
         }
     }
 
@@ -145,6 +147,7 @@ impl ResizableHistogram {
             let idx = (*self.settings.get()).counts_array_index(value);
 
             if idx < (*self.settings.get()).counts_array_length {
+                self.update_min_and_max(value);
                 {
                     let _csg = self.wrp.begin_writer_critical_section();
                     let active_counts = &*self.active_counts.load(Ordering::Relaxed);
@@ -153,13 +156,10 @@ impl ResizableHistogram {
                         active_counts.normalizing_index_offset(),
                         active_counts.length(),
                     );
-                    let c = active_counts.get_unchecked(normalized_index);
-                    c.fetch_add(count, Ordering::Relaxed);
+                    concurrent_util::add_count(active_counts.get_unchecked(normalized_index), count)
                 }
-                self.update_min_and_max(value);
-                self.total_count.fetch_add(count, Ordering::Relaxed);
-                Ok(())
             } else if !self.is_auto_resize() {
+                self.update_min_and_max(value);
                 {
                     let _csg = self.wrp.begin_writer_critical_section();
                     let active_counts = &*self.active_counts.load(Ordering::Relaxed);
@@ -169,12 +169,8 @@ impl ResizableHistogram {
                         active_counts.normalizing_index_offset(),
                         active_counts.length(),
                     );
-                    let c = active_counts.get_unchecked(normalized_index);
-                    c.fetch_add(count, Ordering::Relaxed);
+                    concurrent_util::add_count(active_counts.get_unchecked(normalized_index), count)
                 }
-                self.update_min_and_max(value);
-                self.total_count.fetch_add(count, Ordering::Relaxed);
-                Ok(())
             } else {
                 self.resize_and_record(value, idx, count)
             }
@@ -185,7 +181,9 @@ impl ResizableHistogram {
     fn resize_and_record(&self, value: u64, idx: u32, count: u64) -> Result<(), RecordError> {
         unsafe {
             self.resize(value)
-                .map(|_| {
+                .map_err(|e| RecordError::ResizeFailed(e))
+                .and_then(|_| {
+                    self.update_min_and_max(value);
                     {
                         let _csg = self.wrp.begin_writer_critical_section();
                         let active_counts = &*self.active_counts.load(Ordering::Relaxed);
@@ -194,15 +192,10 @@ impl ResizableHistogram {
                             active_counts.normalizing_index_offset(),
                             active_counts.length(),
                         );
-                        active_counts
-                            .get_unchecked(normalized_index)
-                            .fetch_add(count, Ordering::Relaxed); // should probably be consume for arm?
+                        // should probably be consume for arm?
+                        concurrent_util::add_count(active_counts.get_unchecked(normalized_index), count)
                     }
-
-                    self.update_min_and_max(value);
-                    self.total_count.fetch_add(count, Ordering::Relaxed);
                 })
-                .map_err(|e| RecordError::ResizeFailed(e))
         }
     }
 
@@ -229,7 +222,7 @@ impl ResizableHistogram {
         if number_of_binary_orders_of_magnitude == 0 {
             return Ok(());
         }
-        if self.get_total_count() == self.unsafe_get_count_at_index(0) {
+        if !self.has_values_above_zero_bucket() {
             return Ok(());
         }
 
@@ -257,7 +250,7 @@ impl ResizableHistogram {
         if number_of_binary_orders_of_magnitude == 0 {
             return Ok(());
         }
-        if self.get_total_count() == self.unsafe_get_count_at_index(0) {
+        if !self.has_values_above_zero_bucket() {
             return Ok(());
         }
 
@@ -319,7 +312,7 @@ impl ResizableHistogram {
 
     fn set_normalizing_index_offset_for_inactive(
         &self,
-        inactive_counts: &InlineBackingArray<AtomicU64>,
+        inactive_counts: &InlineBackingArray<C>,
         new_normalizing_index_offset: i32,
         shifted_amount: i32,
         lowest_half_bucket_populated: bool,
@@ -368,7 +361,7 @@ impl ResizableHistogram {
 
     fn shift_lowest_inactive_half_bucket_contents_left(
         &self,
-        inactive_counts: &InlineBackingArray<AtomicU64>,
+        inactive_counts: &InlineBackingArray<C>,
         shift_amount: u32,
         pre_shift_zero_index: u32,
     ) {
@@ -436,8 +429,21 @@ impl ResizableHistogram {
         }
     }
 
+    /// Sums the counts rather than maintaining a shared total on the record path, so this is
+    /// linear in the counts array length. Snapshots compute it once. Takes the reader lock, so
+    /// it waits for a concurrent resize to finish rather than reading arrays being replaced.
     pub fn get_total_count(&self) -> u64 {
-        self.total_count.load(Ordering::Relaxed)
+        let _lg = self.wrp.reader_lock();
+        unsafe {
+            let active_counts = &*self.active_counts.load(Ordering::Acquire);
+            let inactive_counts = &*self.inactive_counts.load(Ordering::Relaxed);
+            concurrent_util::sum_counts(active_counts) + concurrent_util::sum_counts(inactive_counts)
+        }
+    }
+
+    // whether any count lies outside index 0, without summing the counts
+    pub(crate) fn has_values_above_zero_bucket(&self) -> bool {
+        self.raw_min_non_zero_value.load(Ordering::Relaxed) != ORIGINAL_MIN
     }
     pub fn get_max_value(&self) -> u64 {
         self.settings()
@@ -457,7 +463,7 @@ impl ResizableHistogram {
         }
     }
 
-    unsafe fn copy_counts(&self, source: &InlineBackingArray<AtomicU64>, target: &mut InlineBackingArray<AtomicU64>) {
+    unsafe fn copy_counts(&self, source: &InlineBackingArray<C>, target: &mut InlineBackingArray<C>) {
         ptr::copy_nonoverlapping(
             source.get_array_ptr(),
             (*target).get_array_ptr(),
@@ -468,8 +474,8 @@ impl ResizableHistogram {
 
     unsafe fn copy_inactive_counts_contents_on_resize(
         &self,
-        old_inactive: &InlineBackingArray<AtomicU64>,
-        new_inactive: &InlineBackingArray<AtomicU64>,
+        old_inactive: &InlineBackingArray<C>,
+        new_inactive: &InlineBackingArray<C>,
         counts_delta: u32,
     ) {
         let old_zero_index = util::normalize_index(
@@ -528,8 +534,8 @@ impl ResizableHistogram {
             }
             let counts_delta = new_array_length - settings.counts_array_length;
 
-            let new_inactive_counts_1 = InlineBackingArray::<AtomicU64>::new(new_array_length);
-            let new_inactive_counts_2 = InlineBackingArray::<AtomicU64>::new(new_array_length);
+            let new_inactive_counts_1 = InlineBackingArray::<C>::new(new_array_length);
+            let new_inactive_counts_2 = InlineBackingArray::<C>::new(new_array_length);
 
             let previous_inactive_counts = self.inactive_counts.load(Ordering::Relaxed);
             self.inactive_counts.store(new_inactive_counts_1, Ordering::SeqCst);
@@ -585,7 +591,6 @@ impl ResizableHistogram {
                 .get_unchecked(ix)
                 .store(0, Ordering::Relaxed);
         }
-        self.total_count.store(0, Ordering::Relaxed);
         self.raw_max_value
             .store(ORIGINAL_MAX | settings.unit_magnitude_mask, Ordering::Relaxed);
         self.raw_min_non_zero_value
@@ -670,9 +675,11 @@ impl ResizableHistogram {
             );
             let inactive_loc = (*inactive_counts).get_unchecked(inactive_index);
             let count = inactive_loc.load(Ordering::Relaxed);
-            (*active_counts)
-                .get_unchecked(active_index)
-                .fetch_add(count, Ordering::Relaxed);
+            let active_loc = (*active_counts).get_unchecked(active_index);
+            // each half of a count fits a counter, their sum might not
+            if !active_loc.try_add(count, Ordering::Relaxed) {
+                active_loc.store(C::MAX, Ordering::Relaxed);
+            }
             inactive_loc.store(0, Ordering::Relaxed);
         }
     }}
@@ -687,9 +694,9 @@ impl ResizableHistogram {
     }
 }
 
-impl ConstructableHistogram for ResizableHistogram {
+impl<C: AtomicCounter> ConstructableHistogram for ResizableHistogramImpl<C> {
     fn new(lowest_discernible_value: u64, highest_trackable_value: u64, significant_value_digits: u8) -> Result<Self, CreationError> {
-        ResizableHistogram::with_low_high_sigvdig(
+        ResizableHistogramImpl::<C>::with_low_high_sigvdig(
             lowest_discernible_value,
             highest_trackable_value,
             significant_value_digits,
@@ -706,7 +713,7 @@ impl ConstructableHistogram for ResizableHistogram {
             Ordering::Relaxed,
         );
         let array_length = self.counts_array_length();
-        let (new_max, new_min, new_total) = util::recalculate_internal_tracking_values(self, array_length);
+        let (new_max, new_min, _) = util::recalculate_internal_tracking_values(self, array_length);
         new_max.map(|mi| {
             let new_max = self.settings()
                 .highest_equivalent_value(self.settings().value_from_index(mi));
@@ -716,16 +723,15 @@ impl ConstructableHistogram for ResizableHistogram {
             let new_min = self.settings().value_from_index(mi);
             self.update_min_non_zero_value(new_min);
         });
-        self.total_count.store(new_total, Ordering::Relaxed);
     }
 }
 
-impl RecordableHistogram for ResizableHistogram {
-    fn fresh(settings: &HistogramSettings) -> Result<ResizableHistogram, CreationError> {
+impl<C: AtomicCounter> RecordableHistogram for ResizableHistogramImpl<C> {
+    fn fresh(settings: &HistogramSettings) -> Result<ResizableHistogramImpl<C>, CreationError> {
         let lowest_discernable = settings.lowest_discernible_value;
         let highest_trackable = settings.highest_trackable_value;
         let sigvdig = settings.number_of_significant_value_digits as u8;
-        ResizableHistogram::with_low_high_sigvdig(lowest_discernable, highest_trackable, sigvdig)
+        ResizableHistogramImpl::<C>::with_low_high_sigvdig(lowest_discernable, highest_trackable, sigvdig)
     }
     #[inline(always)]
     fn meta_data_mut(&mut self) -> &mut HistogramMetaData {
@@ -733,22 +739,22 @@ impl RecordableHistogram for ResizableHistogram {
     }
     #[inline(always)]
     unsafe fn clear_counts(&self) {
-        ResizableHistogram::clear_counts(self);
+        ResizableHistogramImpl::<C>::clear_counts(self);
     }
     fn equals(&mut self, other: &mut Self) -> bool {
-        ResizableHistogram::equals(self, other)
+        ResizableHistogramImpl::<C>::equals(self, other)
     }
     #[inline(always)]
     fn record_value(&self, value: u64) -> Result<(), RecordError> {
-        ResizableHistogram::record_value(self, value)
+        ResizableHistogramImpl::<C>::record_value(self, value)
     }
     #[inline(always)]
     fn record_value_with_count(&self, value: u64, count: u64) -> Result<(), RecordError> {
-        ResizableHistogram::record_value_with_count(self, value, count)
+        ResizableHistogramImpl::<C>::record_value_with_count(self, value, count)
     }
 }
 
-impl ReadableHistogram for ResizableHistogram {
+impl<C: AtomicCounter> ReadableHistogram for ResizableHistogramImpl<C> {
     #[inline(always)]
     fn settings(&self) -> &HistogramSettings {
         unsafe { &*self.settings.get() }
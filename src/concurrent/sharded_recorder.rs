@@ -0,0 +1,119 @@
+use crate::concurrent::single_writer_recorder::{SingleWriterRecorder, SingleWriterSampler};
+use crate::core::*;
+use crate::st::Histogram;
+use parking_lot::Mutex;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static SHARDED_RECORDER_SEQUENCER: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    // this thread's shard of every sharded recorder it has recorded into, by recorder id
+    static SHARDS: RefCell<BTreeMap<usize, SingleWriterRecorder>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// A recorder where every recording thread gets its own `SingleWriterRecorder` shard, registered
+/// the first time the thread records. Writers share no cache lines; `sample` merges the shards.
+///
+/// Shards of exited threads are merged one last time and then dropped. A thread's shards of dropped
+/// recorders are released the next time it registers a new shard.
+pub struct ShardedRecorder {
+    id: usize,
+    lowest_discernible_value: u64,
+    highest_trackable_value: u64,
+    significant_value_digits: u8,
+    shards: Mutex<Vec<SingleWriterSampler>>,
+}
+
+impl ShardedRecorder {
+    pub fn with_low_high_sigvdig(
+        lowest_discernible_value: u64,
+        highest_trackable_value: u64,
+        significant_value_digits: u8,
+    ) -> Result<ShardedRecorder, CreationError> {
+        // validates the settings once, so that creating shards later can't fail
+        Histogram::<u64>::with_low_high_sigvdig(
+            lowest_discernible_value,
+            highest_trackable_value,
+            significant_value_digits,
+        )?;
+        Ok(ShardedRecorder {
+            id: SHARDED_RECORDER_SEQUENCER.fetch_add(1, Ordering::Relaxed),
+            lowest_discernible_value,
+            highest_trackable_value,
+            significant_value_digits,
+            shards: Mutex::new(Vec::new()),
+        })
+    }
+
+    #[inline]
+    pub fn record_value(&self, value: u64) -> Result<(), RecordError> {
+        self.with_shard(|shard| shard.record_value(value))
+    }
+
+    #[inline]
+    pub fn record_value_with_count(&self, value: u64, count: u64) -> Result<(), RecordError> {
+        self.with_shard(|shard| shard.record_value_with_count(value, count))
+    }
+
+    #[inline]
+    pub fn record_value_with_expected_interval(&self, value: u64, expected_interval_between_value_samples: u64) -> Result<(), RecordError> {
+        self.with_shard(|shard| shard.record_value_with_expected_interval(value, expected_interval_between_value_samples))
+    }
+
+    /// The number of threads currently holding a shard.
+    pub fn shard_count(&self) -> usize {
+        self.shards.lock().len()
+    }
+
+    /// Merges the values every shard recorded since the previous sample. The start timestamp is the
+    /// earliest of the shards'. Fails if a shard's values can't be merged, in which case that
+    /// shard's interval is lost and the shards not merged yet keep theirs for the next sample.
+    pub fn sample(&self) -> Result<Histogram<u64>, RecordError> {
+        let mut merged = self.new_histogram();
+        let mut shards = self.shards.lock();
+        let mut index = 0;
+        while index < shards.len() {
+            // a shard whose thread exited before it was sampled holds its final values
+            let exited = !shards[index].has_writer();
+            {
+                let sample = shards[index].locking_sample();
+                merged.add(sample.histogram())?;
+                merged.meta_data.start_timestamp = match (merged.meta_data.start_timestamp, sample.meta_data.start_timestamp) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+            }
+            if exited {
+                shards.swap_remove(index);
+            } else {
+                index += 1;
+            }
+        }
+        merged.meta_data.set_end_now();
+        Ok(merged)
+    }
+
+    fn new_histogram(&self) -> Histogram<u64> {
+        Histogram::<u64>::with_low_high_sigvdig(
+            self.lowest_discernible_value,
+            self.highest_trackable_value,
+            self.significant_value_digits,
+        ).unwrap()
+    }
+
+    #[inline]
+    fn with_shard<R, F: FnOnce(&mut SingleWriterRecorder) -> R>(&self, f: F) -> R {
+        SHARDS.with(|shards| {
+            let mut shards = shards.borrow_mut();
+            if !shards.contains_key(&self.id) {
+                shards.retain(|_, shard| shard.has_samplers());
+                let shard = SingleWriterRecorder::from_histogram(self.new_histogram());
+                self.shards.lock().push(shard.sampler());
+                shards.insert(self.id, shard);
+            }
+            f(shards.get_mut(&self.id).unwrap())
+        })
+    }
+}
@@ -0,0 +1,312 @@
+use crate::core::*;
+use crate::st::{DoubleHistogramImpl, Histogram};
+use parking_lot::{Mutex, MutexGuard};
+use std::ops::Deref;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+use std::thread;
+
+/// A non-atomic histogram a `SingleWriterRecorder` can record into and sample.
+pub trait IntervalHistogram: Send + Sized {
+    /// What empty histograms are built from. The recorder captures it before the writer starts,
+    /// since an auto-resizing histogram rewrites its own settings while recording.
+    type Config: Send + Sync;
+    fn config(&self) -> Self::Config;
+    /// An empty histogram with the given configuration and time unit.
+    fn fresh(config: &Self::Config) -> Self;
+    fn reset(&mut self);
+    fn meta_data_mut(&mut self) -> &mut HistogramMetaData;
+}
+
+/// The settings a `SingleWriterRecorder` creates its `Histogram<u64>`s with.
+#[derive(Clone, Copy, Debug)]
+pub struct HistogramConfig {
+    lowest_discernible_value: u64,
+    highest_trackable_value: u64,
+    significant_value_digits: u8,
+    auto_resize: bool,
+    time_unit: Option<TimeUnit>,
+}
+
+/// The settings a `SingleWriterDoubleRecorder` creates its `DoubleHistogramImpl`s with.
+#[derive(Clone, Copy, Debug)]
+pub struct DoubleHistogramConfig {
+    highest_to_lowest_value_ratio: u64,
+    significant_value_digits: u8,
+    auto_resize: bool,
+    time_unit: Option<TimeUnit>,
+}
+
+impl IntervalHistogram for Histogram<u64> {
+    type Config = HistogramConfig;
+    fn config(&self) -> HistogramConfig {
+        HistogramConfig {
+            lowest_discernible_value: self.get_lowest_discernable_value(),
+            highest_trackable_value: self.get_highest_trackable_value(),
+            significant_value_digits: self.get_number_of_significant_value_digits() as u8,
+            auto_resize: self.is_auto_resize(),
+            time_unit: self.meta_data.time_unit,
+        }
+    }
+    fn fresh(config: &HistogramConfig) -> Histogram<u64> {
+        let mut fresh = Histogram::<u64>::with_low_high_sigvdig(
+            config.lowest_discernible_value,
+            config.highest_trackable_value,
+            config.significant_value_digits,
+        ).unwrap();
+        fresh.set_auto_resize(config.auto_resize);
+        fresh.meta_data.time_unit = config.time_unit;
+        fresh
+    }
+    fn reset(&mut self) {
+        Histogram::reset(self)
+    }
+    fn meta_data_mut(&mut self) -> &mut HistogramMetaData {
+        &mut self.meta_data
+    }
+}
+
+impl<P: OverflowPolicy + Send> IntervalHistogram for DoubleHistogramImpl<P> {
+    type Config = DoubleHistogramConfig;
+    fn config(&self) -> DoubleHistogramConfig {
+        DoubleHistogramConfig {
+            highest_to_lowest_value_ratio: self.get_highest_to_lowest_value_ratio(),
+            significant_value_digits: self.get_number_of_significant_value_digits(),
+            auto_resize: self.is_auto_resize(),
+            time_unit: self.meta_data().time_unit,
+        }
+    }
+    fn fresh(config: &DoubleHistogramConfig) -> DoubleHistogramImpl<P> {
+        let mut fresh = DoubleHistogramImpl::<P>::with_highest_to_lowest_value_ratio(
+            config.highest_to_lowest_value_ratio,
+            config.significant_value_digits,
+        ).unwrap();
+        fresh.set_auto_resize(config.auto_resize);
+        fresh.meta_data_mut().time_unit = config.time_unit;
+        fresh
+    }
+    fn reset(&mut self) {
+        DoubleHistogramImpl::reset(self)
+    }
+    fn meta_data_mut(&mut self) -> &mut HistogramMetaData {
+        DoubleHistogramImpl::meta_data_mut(self)
+    }
+}
+
+/// A recorder for a single writing thread. Values are recorded into a plain `st::Histogram<u64>`
+/// (or `st::DoubleHistogramImpl` for `SingleWriterDoubleRecorder`) with no atomic counter updates;
+/// the writer only publishes an epoch around each record so that a `SingleWriterSampler` can swap
+/// the active histogram out from under it.
+///
+/// Recording takes `&mut self`, so the type system guarantees there is one writer. Samplers can be
+/// cloned and shared with any number of reading threads.
+pub struct SingleWriterRecorder<H: IntervalHistogram = Histogram<u64>> {
+    state: Arc<SingleWriterState<H>>,
+    // sole writer of `state.epoch`, so it keeps the current value locally
+    epoch: usize,
+}
+
+pub type SingleWriterDoubleRecorderImpl<P> = SingleWriterRecorder<DoubleHistogramImpl<P>>;
+pub type SingleWriterDoubleRecorder = SingleWriterDoubleRecorderImpl<ThrowOnOverflow>;
+pub type SaturatingSingleWriterDoubleRecorder = SingleWriterDoubleRecorderImpl<SaturateOnOverflow>;
+
+/// Takes interval samples from a `SingleWriterRecorder`.
+pub struct SingleWriterSampler<H: IntervalHistogram = Histogram<u64>> {
+    state: Arc<SingleWriterState<H>>,
+}
+
+/// The histogram of values recorded since the previous sample. The reader lock is held until the
+/// sample is dropped, so other samplers of the same recorder wait for it.
+pub struct SingleWriterSample<'a, H: IntervalHistogram = Histogram<u64>> {
+    state: &'a SingleWriterState<H>,
+    histogram: Box<H>,
+    _guard: MutexGuard<'a, ()>,
+}
+
+struct SingleWriterState<H: IntervalHistogram> {
+    active_histogram: AtomicPtr<H>,
+    // taken before the writer starts; the active histogram's settings are the writer's to change
+    config: H::Config,
+    // odd while the writer is inside a record
+    epoch: AtomicUsize,
+    reader_lock: Mutex<()>,
+}
+
+impl SingleWriterRecorder {
+    pub fn with_low_high_sigvdig(
+        lowest_discernible_value: u64,
+        highest_trackable_value: u64,
+        significant_value_digits: u8,
+    ) -> Result<SingleWriterRecorder, CreationError> {
+        Histogram::<u64>::with_low_high_sigvdig(
+            lowest_discernible_value,
+            highest_trackable_value,
+            significant_value_digits,
+        ).map(SingleWriterRecorder::from_histogram)
+    }
+
+    #[inline]
+    pub fn record_value(&mut self, value: u64) -> Result<(), RecordError> {
+        self.record_with(|h| h.record_value(value))
+    }
+
+    #[inline]
+    pub fn record_value_with_count(&mut self, value: u64, count: u64) -> Result<(), RecordError> {
+        self.record_with(|h| h.record_value_with_count(value, count))
+    }
+
+    #[inline]
+    pub fn record_value_with_expected_interval(&mut self, value: u64, expected_interval_between_value_samples: u64) -> Result<(), RecordError> {
+        self.record_with(|h| h.record_value_with_expected_interval(value, expected_interval_between_value_samples))
+    }
+}
+
+impl<P: OverflowPolicy + Send> SingleWriterRecorder<DoubleHistogramImpl<P>> {
+    /// An auto-resizing recorder, as with `DoubleHistogramImpl::new`.
+    pub fn new(number_of_significant_value_digits: u8) -> Result<Self, DoubleCreationError> {
+        DoubleHistogramImpl::<P>::new(number_of_significant_value_digits).map(SingleWriterRecorder::from_histogram)
+    }
+
+    pub fn with_highest_to_lowest_value_ratio(
+        highest_to_lowest_value_ratio: u64,
+        number_of_significant_value_digits: u8,
+    ) -> Result<Self, DoubleCreationError> {
+        DoubleHistogramImpl::<P>::with_highest_to_lowest_value_ratio(
+            highest_to_lowest_value_ratio,
+            number_of_significant_value_digits,
+        ).map(SingleWriterRecorder::from_histogram)
+    }
+
+    #[inline]
+    pub fn record_value(&mut self, value: f64) -> Result<(), RecordError> {
+        self.record_with(|h| h.record_value(value))
+    }
+
+    #[inline]
+    pub fn record_value_with_count(&mut self, value: f64, count: u64) -> Result<(), RecordError> {
+        self.record_with(|h| h.record_value_with_count(value, count))
+    }
+
+    #[inline]
+    pub fn record_value_with_expected_interval(&mut self, value: f64, expected_interval_between_value_samples: f64) -> Result<(), RecordError> {
+        self.record_with(|h| h.record_value_with_expected_interval(value, expected_interval_between_value_samples))
+    }
+}
+
+impl<H: IntervalHistogram> SingleWriterRecorder<H> {
+    pub fn from_histogram(mut histogram: H) -> SingleWriterRecorder<H> {
+        // the first interval starts with the recorder
+        let meta_data = histogram.meta_data_mut();
+        if meta_data.start_timestamp.is_none() {
+            meta_data.set_start_now();
+        }
+        let state = SingleWriterState {
+            config: histogram.config(),
+            active_histogram: AtomicPtr::new(Box::into_raw(Box::new(histogram))),
+            epoch: AtomicUsize::new(0),
+            reader_lock: Mutex::new(()),
+        };
+        SingleWriterRecorder {
+            state: Arc::new(state),
+            epoch: 0,
+        }
+    }
+
+    pub fn sampler(&self) -> SingleWriterSampler<H> {
+        SingleWriterSampler {
+            state: self.state.clone(),
+        }
+    }
+
+    // false once every sampler of this recorder has been dropped
+    pub(in crate::concurrent) fn has_samplers(&self) -> bool {
+        Arc::strong_count(&self.state) > 1
+    }
+
+    #[inline]
+    fn record_with<F: FnOnce(&mut H) -> Result<(), RecordError>>(&mut self, record: F) -> Result<(), RecordError> {
+        // The odd epoch must be visible before the active histogram is loaded, and the pointer
+        // swap before a sampler reads the epoch; both sides use SeqCst for that.
+        self.state.epoch.store(self.epoch + 1, Ordering::SeqCst);
+        let result = unsafe { record(&mut *self.state.active_histogram.load(Ordering::SeqCst)) };
+        self.epoch += 2;
+        self.state.epoch.store(self.epoch, Ordering::Release);
+        result
+    }
+}
+
+impl<H: IntervalHistogram> Clone for SingleWriterSampler<H> {
+    fn clone(&self) -> Self {
+        SingleWriterSampler {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<H: IntervalHistogram> SingleWriterSampler<H> {
+    // false once the recorder has been dropped, assuming a single sampler
+    pub(in crate::concurrent) fn has_writer(&self) -> bool {
+        Arc::strong_count(&self.state) > 1
+    }
+
+    pub fn locking_sample(&self) -> SingleWriterSample<'_, H> {
+        let guard = self.state.reader_lock.lock();
+        let fresh = Box::new(H::fresh(&self.state.config));
+        let histogram = unsafe { self.state.swap(fresh) };
+        SingleWriterSample {
+            state: &self.state,
+            histogram,
+            _guard: guard,
+        }
+    }
+}
+
+impl<H: IntervalHistogram> SingleWriterState<H> {
+    // Requires the reader lock. Returns the previously active histogram once the writer is done
+    // with it.
+    unsafe fn swap(&self, mut inactive_histogram: Box<H>) -> Box<H> {
+        inactive_histogram.meta_data_mut().set_start_now();
+        let previous = self.active_histogram
+            .swap(Box::into_raw(inactive_histogram), Ordering::SeqCst);
+        let epoch = self.epoch.load(Ordering::SeqCst);
+        if epoch & 1 == 1 {
+            // a record that may have loaded the previous histogram is in flight
+            while self.epoch.load(Ordering::Acquire) == epoch {
+                thread::yield_now();
+            }
+        }
+        let mut previous = Box::from_raw(previous);
+        previous.meta_data_mut().set_end_now();
+        previous
+    }
+}
+
+impl<'a, H: IntervalHistogram> SingleWriterSample<'a, H> {
+    pub fn histogram(&self) -> &H {
+        &self.histogram
+    }
+
+    /// Recycles this sample's histogram as the active one and samples again.
+    pub fn resample(self) -> Self {
+        let SingleWriterSample { state, mut histogram, _guard } = self;
+        histogram.reset();
+        SingleWriterSample {
+            state,
+            histogram: unsafe { state.swap(histogram) },
+            _guard,
+        }
+    }
+}
+
+impl<'a, H: IntervalHistogram> Deref for SingleWriterSample<'a, H> {
+    type Target = H;
+    fn deref(&self) -> &H {
+        &self.histogram
+    }
+}
+
+impl<H: IntervalHistogram> Drop for SingleWriterState<H> {
+    fn drop(&mut self) {
+        unsafe { drop(Box::from_raw(*self.active_histogram.get_mut())) };
+    }
+}
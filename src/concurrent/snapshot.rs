@@ -1,64 +1,77 @@
 use crate::concurrent::recordable_histogram::RecordableHistogram;
-use crate::core::{HistogramMetaData, HistogramSettings, ReadableHistogram};
+use crate::core::{util, HistogramMetaData, HistogramSettings, ReadableHistogram};
 use crate::iteration::*;
 use std::ops::Deref;
 
-pub struct Snapshot<'a, T: 'a + RecordableHistogram>(&'a mut T);
+pub struct Snapshot<'a, T: 'a + RecordableHistogram> {
+    histogram: &'a mut T,
+    // the concurrent histograms derive their total from the counts, so it is taken once here
+    total_count: u64,
+}
 
 impl<'a, T: RecordableHistogram> Deref for Snapshot<'a, T> {
     type Target = T;
     fn deref(&self) -> &T {
-        self.0 as &T
+        self.histogram as &T
     }
 }
 
 impl<'a, T: RecordableHistogram> Snapshot<'a, T> {
     pub unsafe fn new(histogram: &mut T) -> Snapshot<'_, T> {
-        Snapshot(histogram)
+        let total_count = histogram.get_total_count();
+        Snapshot { histogram, total_count }
     }
 
     pub fn percentiles(&self, percentile_ticks_per_half_distance: u32) -> PercentileIterator<'_, T> {
-        PercentileIterator::new(self.0, percentile_ticks_per_half_distance)
+        PercentileIterator::new(self.histogram, percentile_ticks_per_half_distance)
     }
 
     pub fn linear_bucket_values(&self, value_units_per_bucket: u64) -> LinearIterator<'_, T> {
-        LinearIterator::new(self.0, value_units_per_bucket)
+        LinearIterator::new(self.histogram, value_units_per_bucket)
     }
 
     pub fn logarithmic_bucket_values(&self, value_units_in_first_bucket: u64, log_base: f64) -> LogarithmicIterator<'_, T> {
-        LogarithmicIterator::new(self.0, value_units_in_first_bucket, log_base)
+        LogarithmicIterator::new(self.histogram, value_units_in_first_bucket, log_base)
     }
 
     pub fn all_values(&self) -> AllValuesIterator<'_, T> {
-        AllValuesIterator::new(self.0)
+        AllValuesIterator::new(self.histogram)
     }
 
     pub fn recorded_values(&self) -> RecordedValuesIterator<'_, T> {
-        RecordedValuesIterator::new(self.0)
+        RecordedValuesIterator::new(self.histogram)
+    }
+
+    pub fn get_value_at_percentile(&self, percentile: f64) -> u64 {
+        util::get_value_at_percentile(self, percentile)
+    }
+
+    pub fn get_values_at_percentiles(&self, percentiles: &[f64]) -> Vec<u64> {
+        util::get_values_at_percentiles(self, percentiles)
     }
 
     pub fn equals(&mut self, other: &mut Snapshot<'_, T>) -> bool {
-        let this = &mut *self.0;
-        let other = &mut *other.0;
+        let this = &mut *self.histogram;
+        let other = &mut *other.histogram;
         this.equals(other)
     }
 }
 
 impl<'a, T: RecordableHistogram> ReadableHistogram for Snapshot<'a, T> {
     fn settings(&self) -> &HistogramSettings {
-        self.0.settings()
+        self.histogram.settings()
     }
     fn array_length(&self) -> u32 {
-        self.0.array_length()
+        self.histogram.array_length()
     }
     fn get_total_count(&self) -> u64 {
-        self.0.get_total_count()
+        self.total_count
     }
     fn unsafe_get_count_at_index(&self, idx: u32) -> u64 {
-        self.0.unsafe_get_count_at_index(idx)
+        self.histogram.unsafe_get_count_at_index(idx)
     }
     fn get_max_value(&self) -> u64 {
-        self.0.get_max_value()
+        self.histogram.get_max_value()
     }
-    fn meta_data(&self) -> &HistogramMetaData { self.0.meta_data() }
+    fn meta_data(&self) -> &HistogramMetaData { self.histogram.meta_data() }
 }
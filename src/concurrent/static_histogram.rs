@@ -1,4 +1,5 @@
-use crate::concurrent::{concurrent_util, Snapshot};
+use crate::concurrent::concurrent_util::{self, CachePadded};
+use crate::concurrent::{AtomicCounter, Snapshot};
 use crate::concurrent::inline_backing_array::InlineBackingArray;
 use crate::concurrent::recordable_histogram::RecordableHistogram;
 use crate::core::*;
@@ -7,28 +8,66 @@ use crate::iteration::RecordedValuesIterator;
 use std::convert::TryFrom;
 use std::{mem, ptr};
 use std::cell::UnsafeCell;
-use std::sync::atomic::{AtomicPtr, AtomicU64};
+use std::sync::atomic::{AtomicPtr, AtomicU16, AtomicU32, AtomicU64};
 use std::sync::atomic::Ordering;
 
+pub use crate::core::histogram_settings::counts_array_length_for;
+
+/// Expands to the `StaticHistogram<N>` type whose `N` matches the given parameters, e.g.
+/// `static_histogram!(1, 3600 * 1000 * 1000, 3)`. The arguments must be const expressions. An
+/// optional fourth argument picks the counter, e.g. `static_histogram!(1, 1000, 2, AtomicU32)`.
+#[macro_export]
+macro_rules! static_histogram {
+    ($lowest_discernible_value:expr, $highest_trackable_value:expr, $significant_value_digits:expr) => {
+        $crate::static_histogram!(
+            $lowest_discernible_value,
+            $highest_trackable_value,
+            $significant_value_digits,
+            ::std::sync::atomic::AtomicU64
+        )
+    };
+    ($lowest_discernible_value:expr, $highest_trackable_value:expr, $significant_value_digits:expr, $counter:ty) => {
+        $crate::concurrent::StaticHistogramImpl<
+            {
+                $crate::concurrent::static_histogram::counts_array_length_for(
+                    $lowest_discernible_value,
+                    $highest_trackable_value,
+                    $significant_value_digits,
+                ) as usize
+            },
+            $counter,
+        >
+    };
+}
+
 #[repr(C)]
-pub struct StaticHistogram<const N: usize> {
+pub struct StaticHistogramImpl<const N: usize, C: AtomicCounter> {
     meta_data: HistogramMetaData,
     settings: UnsafeCell<HistogramSettings>,
-    raw_max_value: AtomicU64,
-    raw_min_non_zero_value: AtomicU64,
-    total_count: AtomicU64,
-    pub(in crate::concurrent) counts: AtomicPtr<InlineBackingArray<AtomicU64>>,
+    raw_max_value: CachePadded<AtomicU64>,
+    raw_min_non_zero_value: CachePadded<AtomicU64>,
+    pub(in crate::concurrent) counts: AtomicPtr<InlineBackingArray<C>>,
 }
 
-impl<const N: usize> StaticHistogram<N> {
-    pub fn new(highest_trackable_value: u64, significant_value_digits: u8) -> Result<StaticHistogram<N>, CreationError> {
+pub type StaticHistogram<const N: usize> = StaticHistogramImpl<N, AtomicU64>;
+pub type StaticHistogram32<const N: usize> = StaticHistogramImpl<N, AtomicU32>;
+pub type StaticHistogram16<const N: usize> = StaticHistogramImpl<N, AtomicU16>;
+
+// SAFETY: counts are only accessed through atomics, in an array owned by the histogram and freed
+// only on drop. The settings behind the `UnsafeCell` are written only at construction and by the
+// owning concurrent double histogram while it holds its range lock.
+unsafe impl<const N: usize, C: AtomicCounter> Send for StaticHistogramImpl<N, C> {}
+unsafe impl<const N: usize, C: AtomicCounter> Sync for StaticHistogramImpl<N, C> {}
+
+impl<const N: usize, C: AtomicCounter> StaticHistogramImpl<N, C> {
+    pub fn new(highest_trackable_value: u64, significant_value_digits: u8) -> Result<StaticHistogramImpl<N, C>, CreationError> {
         Self::with_low_high_sigvdig(1, highest_trackable_value, significant_value_digits)
     }
     pub fn with_low_high_sigvdig(
         lowest_discernible_value: u64,
         highest_trackable_value: u64,
         significant_value_digits: u8,
-    ) -> Result<StaticHistogram<N>, CreationError> {
+    ) -> Result<StaticHistogramImpl<N, C>, CreationError> {
         let counts_array_length = u32::try_from(N)
             .map_err(|_| CreationError::RequiresExcessiveArrayLen)?;
         let settings = HistogramSettings::new(
@@ -44,12 +83,11 @@ impl<const N: usize> StaticHistogram<N> {
         }
         unsafe {
             let array_ptr = InlineBackingArray::new(counts_array_length);
-            Ok(StaticHistogram::<N> {
+            Ok(StaticHistogramImpl {
                 meta_data: HistogramMetaData::new(),
                 settings: UnsafeCell::new(settings),
-                raw_max_value: AtomicU64::new(ORIGINAL_MAX),
-                raw_min_non_zero_value: AtomicU64::new(ORIGINAL_MIN),
-                total_count: AtomicU64::new(0),
+                raw_max_value: CachePadded(AtomicU64::new(ORIGINAL_MAX)),
+                raw_min_non_zero_value: CachePadded(AtomicU64::new(ORIGINAL_MIN)),
                 counts: AtomicPtr::new(array_ptr),
             })
         }
@@ -112,11 +150,10 @@ impl<const N: usize> StaticHistogram<N> {
                     counts.normalizing_index_offset(),
                     counts.length(),
                 );
-                let c = counts.get_unchecked(normalized_index);
-                c.fetch_add(count, Ordering::Relaxed);
+                // min and max first, so that a total derived from the counts never covers a
+                // value they don't
                 self.update_min_and_max(value);
-                self.total_count.fetch_add(count, Ordering::Relaxed);
-                Ok(())
+                concurrent_util::add_count(counts.get_unchecked(normalized_index), count)
             } else {
                 let last_idx = counts.length() - 1;
                 let normalized_index = util::normalize_index(
@@ -124,11 +161,8 @@ impl<const N: usize> StaticHistogram<N> {
                     counts.normalizing_index_offset(),
                     counts.length(),
                 );
-                let c = counts.get_unchecked(normalized_index);
-                c.fetch_add(count, Ordering::Relaxed);
                 self.update_min_and_max(value);
-                self.total_count.fetch_add(count, Ordering::Relaxed);
-                Ok(())
+                concurrent_util::add_count(counts.get_unchecked(normalized_index), count)
             }
         }
     }
@@ -157,7 +191,7 @@ impl<const N: usize> StaticHistogram<N> {
         if number_of_binary_orders_of_magnitude == 0 {
             return Ok(());
         }
-        if self.get_total_count() == self.unsafe_get_count_at_index(0) {
+        if !self.has_values_above_zero_bucket() {
             return Ok(());
         }
 
@@ -185,7 +219,7 @@ impl<const N: usize> StaticHistogram<N> {
         if number_of_binary_orders_of_magnitude == 0 {
             return Ok(());
         }
-        if self.get_total_count() == self.unsafe_get_count_at_index(0) {
+        if !self.has_values_above_zero_bucket() {
             return Ok(());
         }
 
@@ -249,7 +283,7 @@ impl<const N: usize> StaticHistogram<N> {
 
     fn shift_lowest_half_bucket_contents_left(
         &self,
-        counts: &InlineBackingArray<AtomicU64>,
+        counts: &InlineBackingArray<C>,
         shift_amount: u32,
         pre_shift_zero_index: u32,
     ) {
@@ -314,8 +348,14 @@ impl<const N: usize> StaticHistogram<N> {
         }
     }
 
+    /// Sums the counts rather than maintaining a shared total on the record path, so this is
+    /// linear in the counts array length. Snapshots compute it once.
     pub fn get_total_count(&self) -> u64 {
-        self.total_count.load(Ordering::Relaxed)
+        concurrent_util::sum_counts(unsafe { &*self.counts.load(Ordering::Relaxed) })
+    }
+    // whether any count lies outside index 0, without summing the counts
+    pub(crate) fn has_values_above_zero_bucket(&self) -> bool {
+        self.raw_min_non_zero_value.load(Ordering::Relaxed) != ORIGINAL_MIN
     }
     pub fn get_max_value(&self) -> u64 {
         self.settings()
@@ -332,7 +372,6 @@ impl<const N: usize> StaticHistogram<N> {
         for i in 0..self.counts_array_length() {
             (*counts).get_unchecked(i).store(0, Ordering::Relaxed);
         }
-        self.total_count.store(0, Ordering::Relaxed);
         self.raw_max_value
             .store(ORIGINAL_MAX | settings.unit_magnitude_mask, Ordering::Relaxed);
         self.raw_min_non_zero_value
@@ -341,7 +380,7 @@ impl<const N: usize> StaticHistogram<N> {
         (*meta_data).clear();
     }
 
-    unsafe fn copy_counts(&self, source: &InlineBackingArray<AtomicU64>, target: &mut InlineBackingArray<AtomicU64>) {
+    unsafe fn copy_counts(&self, source: &InlineBackingArray<C>, target: &mut InlineBackingArray<C>) {
         ptr::copy_nonoverlapping(
             source.get_array_ptr(),
             (*target).get_array_ptr(),
@@ -397,9 +436,9 @@ impl<const N: usize> StaticHistogram<N> {
     }
 }
 
-impl<const N: usize> ConstructableHistogram for StaticHistogram<N> {
+impl<const N: usize, C: AtomicCounter> ConstructableHistogram for StaticHistogramImpl<N, C> {
     fn new(lowest_discernible_value: u64, highest_trackable_value: u64, significant_value_digits: u8) -> Result<Self, CreationError> {
-        StaticHistogram::<N>::with_low_high_sigvdig(
+        StaticHistogramImpl::<N, C>::with_low_high_sigvdig(
             lowest_discernible_value,
             highest_trackable_value,
             significant_value_digits,
@@ -416,7 +455,7 @@ impl<const N: usize> ConstructableHistogram for StaticHistogram<N> {
             Ordering::Relaxed,
         );
         let array_length = self.counts_array_length();
-        let (new_max, new_min, new_total) = util::recalculate_internal_tracking_values(self, array_length);
+        let (new_max, new_min, _) = util::recalculate_internal_tracking_values(self, array_length);
         new_max.map(|mi| {
             let new_max = self.settings()
                 .highest_equivalent_value(self.settings().value_from_index(mi));
@@ -426,16 +465,15 @@ impl<const N: usize> ConstructableHistogram for StaticHistogram<N> {
             let new_min = self.settings().value_from_index(mi);
             self.update_min_non_zero_value(new_min);
         });
-        self.total_count.store(new_total, Ordering::Relaxed);
     }
 }
 
-impl<const N: usize> RecordableHistogram for StaticHistogram<N> {
-    fn fresh(settings: &HistogramSettings) -> Result<StaticHistogram<N>, CreationError> {
+impl<const N: usize, C: AtomicCounter> RecordableHistogram for StaticHistogramImpl<N, C> {
+    fn fresh(settings: &HistogramSettings) -> Result<StaticHistogramImpl<N, C>, CreationError> {
         let lowest_discernable = settings.lowest_discernible_value;
         let highest_trackable = settings.highest_trackable_value;
         let sigvdig = settings.number_of_significant_value_digits as u8;
-        StaticHistogram::<N>::with_low_high_sigvdig(lowest_discernable, highest_trackable, sigvdig)
+        StaticHistogramImpl::<N, C>::with_low_high_sigvdig(lowest_discernable, highest_trackable, sigvdig)
     }
     #[inline(always)]
     fn meta_data_mut(&mut self) -> &mut HistogramMetaData {
@@ -443,22 +481,22 @@ impl<const N: usize> RecordableHistogram for StaticHistogram<N> {
     }
     #[inline(always)]
     unsafe fn clear_counts(&self) {
-        StaticHistogram::<N>::clear_counts(self);
+        StaticHistogramImpl::<N, C>::clear_counts(self);
     }
     fn equals(&mut self, other: &mut Self) -> bool {
-        StaticHistogram::<N>::equals(self, other)
+        StaticHistogramImpl::<N, C>::equals(self, other)
     }
     #[inline(always)]
     fn record_value(&self, value: u64) -> Result<(), RecordError> {
-        StaticHistogram::<N>::record_value(self, value)
+        StaticHistogramImpl::<N, C>::record_value(self, value)
     }
     #[inline(always)]
     fn record_value_with_count(&self, value: u64, count: u64) -> Result<(), RecordError> {
-        StaticHistogram::<N>::record_value_with_count(self, value, count)
+        StaticHistogramImpl::<N, C>::record_value_with_count(self, value, count)
     }
 }
 
-impl<const N: usize> ReadableHistogram for StaticHistogram<N> {
+impl<const N: usize, C: AtomicCounter> ReadableHistogram for StaticHistogramImpl<N, C> {
     #[inline(always)]
     fn settings(&self) -> &HistogramSettings {
         unsafe { &*self.settings.get() }
@@ -473,7 +511,7 @@ impl<const N: usize> ReadableHistogram for StaticHistogram<N> {
         self.unsafe_get_count_at_index(idx)
     }
     fn get_max_value(&self) -> u64 {
-        StaticHistogram::<N>::get_max_value(self)
+        StaticHistogramImpl::<N, C>::get_max_value(self)
     }
     fn meta_data(&self) -> &HistogramMetaData { &self.meta_data }
 }
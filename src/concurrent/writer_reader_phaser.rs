@@ -11,19 +11,31 @@
 //! ```let rg = ri.reader_lock()```
 //! before finally calling `rg.flip()` once they are done executing the swap.
 
-use parking_lot::{Mutex, MutexGuard};
+use parking_lot::{Condvar, Mutex};
+use std::cmp;
+use std::hint;
 use std::isize::MIN as ISIZE_MIN;
 use std::mem;
-use std::sync::atomic::{AtomicIsize, Ordering};
-use std::thread;
-use std::time::Duration;
+use std::sync::atomic::{self, AtomicIsize, Ordering};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+// Set in the end epoch of the phase a reader waits on with `WaitStrategy::Park`. Epochs count
+// from 0 or `ISIZE_MIN` and never get near this bit, so writers learn a reader is parked from the
+// value their exit increment returns, without an extra load.
+const READER_PARKED_BIT: isize = 1 << (isize::BITS - 2);
 
 // Struct holding all the bookkeeping variables for the phaser
 pub struct WriterReaderPhaser {
     start_epoch: AtomicIsize,
     even_end_epoch: AtomicIsize,
     odd_end_epoch: AtomicIsize,
-    reader_lock: Mutex<()>,
+    // whether a reader holds the reader lock. The lock is a flag rather than a mutex guard, so
+    // that a `PhaseFlipGuard` can be sent to and released on another thread.
+    reader_locked: Mutex<bool>,
+    reader_unlocked: Condvar,
+    // the reader waiting with `WaitStrategy::Park`, if one is parked
+    parked_reader: Mutex<Option<Thread>>,
 }
 
 impl WriterReaderPhaser {
@@ -36,7 +48,9 @@ impl WriterReaderPhaser {
             start_epoch: start,
             even_end_epoch: even_end,
             odd_end_epoch: odd_end,
-            reader_lock: Mutex::new(()),
+            reader_locked: Mutex::new(false),
+            reader_unlocked: Condvar::new(),
+            parked_reader: Mutex::new(None),
         }
     }
 
@@ -44,25 +58,49 @@ impl WriterReaderPhaser {
         let critical_value = self.start_epoch.fetch_add(1, Ordering::Acquire);
         if critical_value < 0 {
             WriterCriticalSectionGuard {
+                parent: self,
                 epoch: &self.odd_end_epoch,
             }
         } else {
             WriterCriticalSectionGuard {
+                parent: self,
                 epoch: &self.even_end_epoch,
             }
         }
     }
 
     pub fn reader_lock<'a>(&'a self) -> PhaseFlipGuard<'a> {
-        let guard = self.reader_lock.lock();
-        PhaseFlipGuard {
-            parent: &self,
-            _guard: guard,
+        let mut locked = self.reader_locked.lock();
+        while *locked {
+            self.reader_unlocked.wait(&mut locked);
+        }
+        *locked = true;
+        PhaseFlipGuard { parent: self }
+    }
+
+    /// As `reader_lock`, but returns `None` instead of blocking while another reader holds the lock.
+    pub fn try_reader_lock<'a>(&'a self) -> Option<PhaseFlipGuard<'a>> {
+        let mut locked = self.reader_locked.lock();
+        if *locked {
+            return None;
+        }
+        *locked = true;
+        Some(PhaseFlipGuard { parent: self })
+    }
+
+    // called by a writer leaving its critical section while a reader is parked
+    #[cold]
+    fn unpark_reader(&self) {
+        // pairs with the release of the parked bit, making the parked reader's registration visible
+        atomic::fence(Ordering::Acquire);
+        if let Some(reader) = self.parked_reader.lock().as_ref() {
+            reader.unpark();
         }
     }
 }
 
 pub struct WriterCriticalSectionGuard<'a> {
+    parent: &'a WriterReaderPhaser,
     epoch: &'a AtomicIsize,
 }
 
@@ -75,18 +113,123 @@ impl<'a> WriterCriticalSectionGuard<'a> {
 impl<'a> Drop for WriterCriticalSectionGuard<'a> {
     #[allow(unused_results)]
     fn drop(&mut self) {
-        self.epoch.fetch_add(1, Ordering::Release);
+        // the reader sets the parked bit with an RMW on the same epoch, so either this sees the
+        // bit or the reader sees this exit before it parks
+        let previous = self.epoch.fetch_add(1, Ordering::Release);
+        if previous & READER_PARKED_BIT != 0 {
+            self.parent.unpark_reader();
+        }
     }
 }
 
 // Guard used to enforce lock before flip
 pub struct PhaseFlipGuard<'a> {
     parent: &'a WriterReaderPhaser,
-    _guard: MutexGuard<'a, ()>,
+}
+
+impl<'a> Drop for PhaseFlipGuard<'a> {
+    fn drop(&mut self) {
+        *self.parent.reader_locked.lock() = false;
+        self.parent.reader_unlocked.notify_one();
+    }
+}
+
+/// How a reader waits for the writers of the phase it flipped away from.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum WaitStrategy {
+    /// Yields the thread between checks.
+    #[default]
+    Yield,
+    /// Sleeps a fixed time between checks.
+    Sleep(Duration),
+    /// Spins the given number of times before yielding between checks.
+    SpinThenYield(u32),
+    /// Sleeps between checks, starting at `initial` and doubling up to `max`.
+    ExponentialBackoff { initial: Duration, max: Duration },
+    /// Parks the reader until a writer of the phase leaves its critical section and unparks it.
+    Park,
+}
+
+/// Returned when the writers of a flipped phase didn't catch up before the deadline. The flip
+/// stays pending and must be waited for before the reader lock is released or the next flip.
+#[derive(Clone, Copy, Debug)]
+pub struct FlipTimeout {
+    pub pending: PendingFlip,
+    pub writers_in_flight: usize,
+}
+
+/// A flip that has been started by `PhaseFlipGuard::begin_flip` but whose writers may not have
+/// caught up yet.
+#[derive(Clone, Copy, Debug)]
+pub struct PendingFlip {
+    next_phase_is_even: bool,
+    start_value_at_flip: isize,
 }
 
 impl<'a> PhaseFlipGuard<'a> {
     pub fn flip_with_yield_time(&self, yield_time: Duration) {
+        self.timed_flip_with_yield_time(yield_time);
+    }
+
+    pub fn flip(&self) {
+        self.timed_flip();
+    }
+
+    /// As `flip_with_yield_time`, returning how long the writers took to catch up.
+    pub fn timed_flip_with_yield_time(&self, yield_time: Duration) -> Duration {
+        if yield_time.is_zero() {
+            self.flip_with_wait_strategy(WaitStrategy::Yield)
+        } else {
+            self.flip_with_wait_strategy(WaitStrategy::Sleep(yield_time))
+        }
+    }
+
+    /// As `flip`, returning how long the writers took to catch up.
+    pub fn timed_flip(&self) -> Duration {
+        self.flip_with_wait_strategy(WaitStrategy::Yield)
+    }
+
+    /// Flips, waiting for the writers with `strategy`, and returns how long they took to catch up.
+    pub fn flip_with_wait_strategy(&self, strategy: WaitStrategy) -> Duration {
+        let started = Instant::now();
+        let pending = self.begin_flip();
+        self.wait_for_flip(&pending, strategy, None);
+        started.elapsed()
+    }
+
+    /// Flips, giving up waiting for the writers at `deadline`. On success, returns how long the
+    /// writers took to catch up.
+    pub fn try_flip_until(&self, deadline: Instant, strategy: WaitStrategy) -> Result<Duration, FlipTimeout> {
+        let started = Instant::now();
+        let pending = self.begin_flip();
+        self.wait_for_flip_until(&pending, deadline, strategy)?;
+        Ok(started.elapsed())
+    }
+
+    /// Waits for a pending flip, e.g. one that timed out, until `deadline`. On success, returns how
+    /// long this call waited.
+    pub fn wait_for_flip_until(&self, pending: &PendingFlip, deadline: Instant, strategy: WaitStrategy) -> Result<Duration, FlipTimeout> {
+        let started = Instant::now();
+        if self.wait_for_flip(pending, strategy, Some(deadline)) {
+            Ok(started.elapsed())
+        } else {
+            Err(FlipTimeout {
+                pending: *pending,
+                writers_in_flight: self.writers_in_flight(pending),
+            })
+        }
+    }
+
+    /// The number of writers of the phase `pending` flipped away from that are still in their
+    /// critical sections.
+    pub fn writers_in_flight(&self, pending: &PendingFlip) -> usize {
+        let end_value = self.flipped_end_epoch(pending).load(Ordering::Relaxed) & !READER_PARKED_BIT;
+        pending.start_value_at_flip.wrapping_sub(end_value) as usize
+    }
+
+    /// Starts a flip without waiting for the writers of the previous phase. The flip is done once
+    /// `is_flip_complete` returns true, and the next flip must not begin before that.
+    pub fn begin_flip(&self) -> PendingFlip {
         let next_phase_is_even = self.parent.start_epoch.load(Ordering::SeqCst) < 0;
 
         let initial_start_value = if next_phase_is_even { 0 } else { ISIZE_MIN };
@@ -104,29 +247,69 @@ impl<'a> PhaseFlipGuard<'a> {
             .start_epoch
             .swap(initial_start_value, Ordering::SeqCst);
 
-        loop {
-            let caught_up = if next_phase_is_even {
-                self.parent.odd_end_epoch.load(Ordering::Relaxed) == start_value_at_flip
-            } else {
-                self.parent.even_end_epoch.load(Ordering::Relaxed) == start_value_at_flip
-            };
-            if !caught_up {
-                if yield_time.as_secs() == 0 && yield_time.subsec_nanos() == 0 {
-                    thread::yield_now();
-                } else {
-                    thread::sleep(yield_time);
-                }
-            } else {
-                break;
-            }
+        PendingFlip {
+            next_phase_is_even,
+            start_value_at_flip,
         }
     }
 
-    pub fn flip(&self) {
-        self.flip_with_yield_time(Duration::new(0, 0))
+    /// Whether every writer of the phase `pending` flipped away from has left its critical section.
+    pub fn is_flip_complete(&self, pending: &PendingFlip) -> bool {
+        (self.flipped_end_epoch(pending).load(Ordering::Relaxed) & !READER_PARKED_BIT) == pending.start_value_at_flip
+    }
+
+    // the end epoch of the phase `pending` flipped away from
+    fn flipped_end_epoch(&self, pending: &PendingFlip) -> &'a AtomicIsize {
+        if pending.next_phase_is_even {
+            &self.parent.odd_end_epoch
+        } else {
+            &self.parent.even_end_epoch
+        }
     }
 
     pub fn reader_unlock(self) {
         mem::drop(self)
     }
+
+    // returns false if the deadline passed first
+    fn wait_for_flip(&self, pending: &PendingFlip, strategy: WaitStrategy, deadline: Option<Instant>) -> bool {
+        let mut waits = 0_u32;
+        while !self.is_flip_complete(pending) {
+            let remaining = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(remaining) if !remaining.is_zero() => Some(remaining),
+                    _ => return false,
+                },
+                None => None,
+            };
+            let bounded = |sleep: Duration| remaining.map_or(sleep, |remaining| cmp::min(sleep, remaining));
+            match strategy {
+                WaitStrategy::Yield => thread::yield_now(),
+                WaitStrategy::Sleep(sleep) => thread::sleep(bounded(sleep)),
+                WaitStrategy::SpinThenYield(spins) if waits < spins => hint::spin_loop(),
+                WaitStrategy::SpinThenYield(_) => thread::yield_now(),
+                WaitStrategy::ExponentialBackoff { initial, max } => {
+                    let backoff = initial.checked_mul(1 << cmp::min(waits, 31)).unwrap_or(max);
+                    thread::sleep(bounded(cmp::min(backoff, max)));
+                }
+                WaitStrategy::Park => self.park(pending, remaining),
+            }
+            waits = waits.saturating_add(1);
+        }
+        true
+    }
+
+    fn park(&self, pending: &PendingFlip, timeout: Option<Duration>) {
+        *self.parent.parked_reader.lock() = Some(thread::current());
+        let end_epoch = self.flipped_end_epoch(pending);
+        let end_value = end_epoch.fetch_or(READER_PARKED_BIT, Ordering::AcqRel);
+        if end_value & !READER_PARKED_BIT != pending.start_value_at_flip {
+            match timeout {
+                Some(timeout) => thread::park_timeout(timeout),
+                None => thread::park(),
+            }
+        }
+        end_epoch.fetch_and(!READER_PARKED_BIT, Ordering::Relaxed);
+        *self.parent.parked_reader.lock() = None;
+    }
 }
@@ -20,6 +20,9 @@ pub enum RecordError {
     ValueOutOfRangeResizeDisabled,
     ResizeFailed(CreationError),
     DoubleCreationFailed(DoubleCreationError),
+    CountOverflow,
+    SubtractionFailed(SubtractionError),
+    TimeUnitMismatch,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -30,14 +33,38 @@ pub enum DoubleCreationError {
     Internal(CreationError),
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DecayingCreationError {
+    HalfLifeIsZero,
+    Internal(CreationError),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SlidingWindowCreationError {
+    IntervalCountIsZero,
+    Internal(CreationError),
+}
+
 impl From<DoubleCreationError> for RecordError {
     fn from(err: DoubleCreationError) -> Self {
         RecordError::DoubleCreationFailed(err)
     }
 }
 
+impl From<SubtractionError> for RecordError {
+    fn from(err: SubtractionError) -> Self {
+        RecordError::SubtractionFailed(err)
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ShiftError {
     Underflow,
     Overflow,
 }
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TagError {
+    Empty,
+    InvalidCharacter(char),
+}
@@ -27,6 +27,87 @@ macro_rules! expect {
     };
 }
 
+// The parts of `HistogramSettings::new` that determine the array layout. These are const so the
+// counts array length of a `StaticHistogram` can be computed at compile time.
+const fn validate(
+    lowest_discernible_value: u64,
+    highest_trackable_value: u64,
+    significant_value_digits: u8,
+) -> Result<(), CreationError> {
+    if lowest_discernible_value < 1 {
+        return Err(CreationError::LowIsZero);
+    }
+    if lowest_discernible_value > u64::MAX / 2 {
+        return Err(CreationError::LowGtMax);
+    }
+    if highest_trackable_value < 2 * lowest_discernible_value {
+        return Err(CreationError::HighLt2Low);
+    }
+    if significant_value_digits > 5 {
+        return Err(CreationError::SignificantValueDigitsExceedsMax);
+    }
+    if unit_magnitude_for(lowest_discernible_value) + sub_bucket_count_magnitude_for(significant_value_digits) > 63 {
+        return Err(CreationError::CantReprSigDigitsLtLowestDiscernible);
+    }
+    Ok(())
+}
+
+// floor(log2(lowest_discernible_value))
+const fn unit_magnitude_for(lowest_discernible_value: u64) -> u32 {
+    63 - lowest_discernible_value.leading_zeros()
+}
+
+// ceil(log2(2 * 10^significant_value_digits))
+const fn sub_bucket_count_magnitude_for(significant_value_digits: u8) -> u32 {
+    let largest_value_with_single_unit_resolution = 2 * 10_u64.pow(significant_value_digits as u32);
+    64 - (largest_value_with_single_unit_resolution - 1).leading_zeros()
+}
+
+const fn buckets_needed_to_cover_value(sub_bucket_count: u32, unit_magnitude: u32, value: u64) -> u32 {
+    let mut smallest_untrackable_value = (sub_bucket_count as u64) << unit_magnitude;
+
+    let mut buckets_needed = 1;
+    while smallest_untrackable_value <= value {
+        if smallest_untrackable_value > u64::MAX / 2 {
+            return buckets_needed + 1;
+        }
+        smallest_untrackable_value <<= 1;
+        buckets_needed += 1;
+    }
+    buckets_needed
+}
+
+const fn length_for_number_of_buckets(sub_bucket_half_count: u32, number_of_buckets: u32) -> u32 {
+    (number_of_buckets + 1) * sub_bucket_half_count
+}
+
+/// Returns the counts array length a histogram with the given parameters requires. Being a
+/// `const fn`, it can supply the `N` of a `StaticHistogram<N>`; see `static_histogram!`.
+///
+/// Panics (at compile time when evaluated in a const context) if `HistogramSettings::new`
+/// would reject the parameters.
+pub const fn counts_array_length_for(
+    lowest_discernible_value: u64,
+    highest_trackable_value: u64,
+    significant_value_digits: u8,
+) -> u32 {
+    match validate(lowest_discernible_value, highest_trackable_value, significant_value_digits) {
+        Ok(()) => (),
+        Err(CreationError::LowIsZero) => panic!("lowest discernible value must be >= 1"),
+        Err(CreationError::LowGtMax) => panic!("lowest discernible value must be <= u64::MAX / 2"),
+        Err(CreationError::HighLt2Low) => panic!("highest trackable value must be >= 2 * lowest discernible value"),
+        Err(CreationError::SignificantValueDigitsExceedsMax) => panic!("significant value digits must be <= 5"),
+        Err(_) => panic!("cannot represent significant value digits beyond lowest discernible value"),
+    }
+    let unit_magnitude = unit_magnitude_for(lowest_discernible_value);
+    let sub_bucket_count = 1_u32 << sub_bucket_count_magnitude_for(significant_value_digits);
+    let buckets_needed = buckets_needed_to_cover_value(sub_bucket_count, unit_magnitude, highest_trackable_value);
+    if buckets_needed > i32::MAX as u32 {
+        panic!("requires excessive array length");
+    }
+    length_for_number_of_buckets(sub_bucket_count / 2, buckets_needed)
+}
+
 #[allow(dead_code)]
 impl HistogramSettings {
     pub fn new(
@@ -34,36 +115,15 @@ impl HistogramSettings {
         highest_trackable_value: u64,
         significant_value_digits: u8,
     ) -> Result<HistogramSettings, CreationError> {
-        expect!(lowest_discernible_value < 1, CreationError::LowIsZero);
-        expect!(
-            lowest_discernible_value > u64::MAX / 2,
-            CreationError::LowGtMax
-        );
-        expect!(
-            highest_trackable_value < 2 * lowest_discernible_value,
-            CreationError::HighLt2Low
-        );
-        expect!(
-            significant_value_digits > 5,
-            CreationError::SignificantValueDigitsExceedsMax
-        );
-
-        let largest_value_with_single_unit_resolution = 2 * 10_u64.pow(u32::from(significant_value_digits));
+        validate(lowest_discernible_value, highest_trackable_value, significant_value_digits)?;
 
-        let unit_magnitude = (lowest_discernible_value as f64).log2().floor() as u32;
+        let unit_magnitude = unit_magnitude_for(lowest_discernible_value);
         let unit_magnitude_mask = (1 << unit_magnitude) - 1;
 
-        let sub_bucket_count_magnitude = (largest_value_with_single_unit_resolution as f64)
-            .log2()
-            .ceil() as u32;
+        let sub_bucket_count_magnitude = sub_bucket_count_magnitude_for(significant_value_digits);
         let sub_bucket_half_count_magnitude = sub_bucket_count_magnitude - 1;
         let sub_bucket_count = 1 << sub_bucket_count_magnitude;
 
-        expect!(
-            unit_magnitude + sub_bucket_count_magnitude > 63,
-            CreationError::CantReprSigDigitsLtLowestDiscernible
-        );
-
         let sub_bucket_half_count = sub_bucket_count / 2;
 
         let sub_bucket_mask = (sub_bucket_count as u64 - 1) << unit_magnitude;
@@ -165,22 +225,12 @@ impl HistogramSettings {
 
     #[inline(always)]
     pub fn get_buckets_needed_to_cover_value(&self, value: u64) -> u32 {
-        let mut smallest_untrackable_value = (self.sub_bucket_count as u64) << self.unit_magnitude;
-
-        let mut buckets_needed = 1;
-        while smallest_untrackable_value <= value {
-            if smallest_untrackable_value > u64::MAX / 2 {
-                return buckets_needed + 1;
-            }
-            smallest_untrackable_value <<= 1;
-            buckets_needed += 1;
-        }
-        buckets_needed
+        buckets_needed_to_cover_value(self.sub_bucket_count, self.unit_magnitude, value)
     }
 
     #[inline(always)]
     pub fn get_length_for_number_of_buckets(&self, number_of_buckets: u32) -> u32 {
-        (number_of_buckets + 1) * (self.sub_bucket_half_count)
+        length_for_number_of_buckets(self.sub_bucket_half_count, number_of_buckets)
     }
 
     #[inline(always)]
@@ -1,9 +1,13 @@
+use crate::core::time_unit::TimeUnit;
 use std::time::SystemTime;
 
 pub struct HistogramMetaData {
     pub start_timestamp: Option<SystemTime>,
     pub end_timestamp: Option<SystemTime>,
     pub tag: Option<String>,
+    /// Unit of the recorded values, if they are durations. Unlike the other fields it describes
+    /// the histogram rather than an interval, so `clear` keeps it.
+    pub time_unit: Option<TimeUnit>,
 }
 
 impl HistogramMetaData {
@@ -12,6 +16,7 @@ impl HistogramMetaData {
             start_timestamp: None,
             end_timestamp: None,
             tag: None,
+            time_unit: None,
         }
     }
     pub fn clear(&mut self) {
@@ -28,6 +33,9 @@ impl HistogramMetaData {
     pub fn set_tag_string(&mut self, tag_string: String) {
         self.tag = Some(tag_string);
     }
+    pub fn set_time_unit(&mut self, time_unit: TimeUnit) {
+        self.time_unit = Some(time_unit);
+    }
     pub fn set_start_now(&mut self) {
         self.start_timestamp = Some(SystemTime::now());
     }
@@ -8,6 +8,7 @@ pub mod counter;
 pub mod readable_histogram;
 pub mod constructable_histogram;
 pub mod double_policy;
+pub mod time_unit;
 
 pub use self::counter::Counter;
 
@@ -16,4 +17,5 @@ pub use self::errors::*;
 pub use self::histogram_settings::HistogramSettings;
 pub use self::meta_data::HistogramMetaData;
 pub use self::readable_histogram::ReadableHistogram;
+pub use self::time_unit::TimeUnit;
 pub use self::double_policy::{OverflowPolicy, ThrowOnOverflow, SaturateOnOverflow};
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+/// The unit a histogram's integer values are measured in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TimeUnit {
+    Nanoseconds,
+    Microseconds,
+    Milliseconds,
+}
+
+impl TimeUnit {
+    pub fn nanos_per_unit(&self) -> u64 {
+        match *self {
+            TimeUnit::Nanoseconds => 1,
+            TimeUnit::Microseconds => 1_000,
+            TimeUnit::Milliseconds => 1_000_000,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match *self {
+            TimeUnit::Nanoseconds => "ns",
+            TimeUnit::Microseconds => "us",
+            TimeUnit::Milliseconds => "ms",
+        }
+    }
+
+    /// What values in this unit are divided by to express them in `output`.
+    pub fn scaling_ratio_to(&self, output: TimeUnit) -> f64 {
+        output.nanos_per_unit() as f64 / self.nanos_per_unit() as f64
+    }
+
+    /// Whole units in `duration`, truncated and saturating at `u64::MAX`.
+    pub fn to_value(&self, duration: Duration) -> u64 {
+        let value = duration.as_nanos() / u128::from(self.nanos_per_unit());
+        if value > u128::from(u64::MAX) {
+            u64::MAX
+        } else {
+            value as u64
+        }
+    }
+
+    pub fn to_duration(&self, value: u64) -> Duration {
+        let nanos = u128::from(value) * u128::from(self.nanos_per_unit());
+        Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+    }
+
+    /// Converts a fractional value such as a mean, rounded to the nearest nanosecond.
+    pub fn to_duration_f64(&self, value: f64) -> Duration {
+        let nanos = (value * self.nanos_per_unit() as f64).max(0.0).round();
+        Duration::new((nanos / 1.0e9).floor() as u64, (nanos % 1.0e9) as u32)
+    }
+}
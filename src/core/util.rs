@@ -75,3 +75,64 @@ pub fn recalculate_internal_tracking_values<H: ReadableHistogram>(histogram: &mu
     }
     (new_max, new_min, new_total)
 }
+
+// Number of recorded values that must be at or below the value reported for `percentile`.
+pub(crate) fn count_at_percentile(total_count: u64, percentile: f64) -> u64 {
+    let one_below = next_below(percentile);
+    let requested_percentile = one_below.clamp(0.0, 100.0);
+
+    let fractional_count = (requested_percentile / 100.0) * total_count as f64;
+    // Make sure we at least reach the first recorded entry
+    std::cmp::max(fractional_count.ceil() as u64, 1)
+}
+
+pub(crate) fn value_at_percentile_index<H: ReadableHistogram>(histogram: &H, index: u32, percentile: f64) -> u64 {
+    let settings = histogram.settings();
+    let value_at_index = settings.value_from_index(index);
+    if percentile == 0.0 {
+        settings.lowest_equivalent_value(value_at_index)
+    } else {
+        settings.highest_equivalent_value(value_at_index)
+    }
+}
+
+pub fn get_value_at_percentile<H: ReadableHistogram>(histogram: &H, percentile: f64) -> u64 {
+    let count_at_percentile = count_at_percentile(histogram.get_total_count(), percentile);
+
+    let mut total_to_current_index: u64 = 0;
+    for i in 0..histogram.array_length() {
+        total_to_current_index += histogram.unsafe_get_count_at_index(i);
+        if total_to_current_index >= count_at_percentile {
+            return value_at_percentile_index(histogram, i, percentile);
+        }
+    }
+
+    0
+}
+
+/// Values at each of `percentiles`, in the order given, found in a single scan of the counts.
+pub fn get_values_at_percentiles<H: ReadableHistogram>(histogram: &H, percentiles: &[f64]) -> Vec<u64> {
+    let total_count = histogram.get_total_count();
+    let mut targets = percentiles
+        .iter()
+        .enumerate()
+        .map(|(i, &percentile)| (count_at_percentile(total_count, percentile), i))
+        .collect::<Vec<_>>();
+    targets.sort_unstable();
+
+    let mut values = vec![0; percentiles.len()];
+    let mut next_target = 0;
+    let mut total_to_current_index: u64 = 0;
+    for i in 0..histogram.array_length() {
+        if next_target == targets.len() {
+            break;
+        }
+        total_to_current_index += histogram.unsafe_get_count_at_index(i);
+        while next_target < targets.len() && total_to_current_index >= targets[next_target].0 {
+            let percentile_index = targets[next_target].1;
+            values[percentile_index] = value_at_percentile_index(histogram, i, percentiles[percentile_index]);
+            next_target += 1;
+        }
+    }
+    values
+}
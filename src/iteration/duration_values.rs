@@ -0,0 +1,41 @@
+use crate::core::TimeUnit;
+use crate::iteration::IterationValue;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DurationIterationValue {
+    pub duration_iterated_to: Duration,
+    pub duration_iterated_from: Duration,
+    pub count_at_value_iterated_to: u64,
+    pub count_added_in_this_iteration_step: u64,
+    pub total_count_to_this_value: u64,
+    pub percentile: f64,
+    pub percentile_level_iterated_to: f64,
+}
+
+/// Adapts any of the histogram iterators to yield durations in the given unit.
+pub struct DurationValues<I> {
+    inner: I,
+    time_unit: TimeUnit,
+}
+
+impl<I: Iterator<Item = IterationValue>> DurationValues<I> {
+    pub fn new(inner: I, time_unit: TimeUnit) -> DurationValues<I> {
+        DurationValues { inner, time_unit }
+    }
+}
+
+impl<I: Iterator<Item = IterationValue>> Iterator for DurationValues<I> {
+    type Item = DurationIterationValue;
+    fn next(&mut self) -> Option<DurationIterationValue> {
+        self.inner.next().map(|v| DurationIterationValue {
+            duration_iterated_to: self.time_unit.to_duration(v.value_iterated_to),
+            duration_iterated_from: self.time_unit.to_duration(v.value_iterated_from),
+            count_at_value_iterated_to: v.count_at_value_iterated_to,
+            count_added_in_this_iteration_step: v.count_added_in_this_iteration_step,
+            total_count_to_this_value: v.total_count_to_this_value,
+            percentile: v.percentile,
+            percentile_level_iterated_to: v.percentile_level_iterated_to,
+        })
+    }
+}
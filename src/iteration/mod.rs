@@ -4,7 +4,9 @@ pub mod iteration_state;
 pub mod histogram_iterator;
 pub mod iteration_strategy;
 pub mod iterators;
+pub mod duration_values;
 
+pub use self::duration_values::{DurationIterationValue, DurationValues};
 pub use self::iteration_state::IterationState;
 pub use self::iteration_strategy::IterationStrategy;
 pub use self::iteration_value::IterationValue;
@@ -7,9 +7,11 @@
 mod core;
 pub mod concurrent;
 pub use crate::core::errors::*;
+pub use crate::core::TimeUnit;
 pub mod encoding;
 pub mod st;
 pub mod iteration;
+pub mod stats;
 
 #[cfg(test)]
 pub mod tests;
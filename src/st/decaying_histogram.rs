@@ -0,0 +1,142 @@
+use crate::core::*;
+use crate::st::Histogram;
+use std::time::{Duration, Instant};
+
+// Weight of a value recorded at the landmark. Later values get exponentially larger weights
+// (forward decay), which leaves the relative weights of older values decaying with the half-life.
+const LANDMARK_WEIGHT_MAGNITUDE: u32 = 16;
+// Once weights have doubled this many times the landmark is moved forward and all counts are
+// scaled down. A single record weighs less than 2^24 before a rescale, leaving room for 2^40
+// records per half-life before the u64 total count overflows.
+const RESCALE_AFTER_HALF_LIVES: u32 = 8;
+
+/// A histogram whose recorded values lose half their weight every `half_life`, giving a
+/// recent-biased distribution without interval resets. Percentiles and the mean reflect the
+/// decayed weights.
+pub struct DecayingHistogram {
+    histogram: Histogram<u64>,
+    half_life: Duration,
+    landmark: Instant,
+}
+
+impl DecayingHistogram {
+    pub fn new(highest_trackable_value: u64, significant_value_digits: u8, half_life: Duration) -> Result<DecayingHistogram, DecayingCreationError> {
+        DecayingHistogram::with_low_high_sigvdig(1, highest_trackable_value, significant_value_digits, half_life)
+    }
+
+    pub fn with_low_high_sigvdig(
+        lowest_discernible_value: u64,
+        highest_trackable_value: u64,
+        significant_value_digits: u8,
+        half_life: Duration,
+    ) -> Result<DecayingHistogram, DecayingCreationError> {
+        if half_life.is_zero() {
+            return Err(DecayingCreationError::HalfLifeIsZero);
+        }
+        let histogram = Histogram::<u64>::with_low_high_sigvdig(
+            lowest_discernible_value,
+            highest_trackable_value,
+            significant_value_digits,
+        )
+        .map_err(DecayingCreationError::Internal)?;
+        Ok(DecayingHistogram {
+            histogram,
+            half_life,
+            landmark: Instant::now(),
+        })
+    }
+
+    pub fn record_value(&mut self, value: u64) -> Result<(), RecordError> {
+        self.record_value_at(value, Instant::now())
+    }
+
+    /// Records `value` as observed at `now`. Instants before the last rescale are treated as
+    /// having been observed at that point.
+    pub fn record_value_at(&mut self, value: u64, now: Instant) -> Result<(), RecordError> {
+        self.record_value_with_count_at(value, 1, now)
+    }
+
+    /// Records `count` occurrences of `value` as observed at `now`. Fails without recording if
+    /// the weighted count would overflow the total count.
+    pub fn record_value_with_count_at(&mut self, value: u64, count: u64, now: Instant) -> Result<(), RecordError> {
+        let mut half_lives = self.half_lives_since_landmark(now);
+        if half_lives >= RESCALE_AFTER_HALF_LIVES as f64 {
+            self.rescale(half_lives.floor(), now);
+            half_lives = self.half_lives_since_landmark(now);
+        }
+        let weight = ((1_u64 << LANDMARK_WEIGHT_MAGNITUDE) as f64 * half_lives.exp2()) as u64;
+        // no bucket holds more than the total count, so checking the total covers both
+        let weighted_count = weight
+            .checked_mul(count)
+            .filter(|&weighted_count| self.histogram.get_total_count().checked_add(weighted_count).is_some())
+            .ok_or(RecordError::CountOverflow)?;
+        self.histogram.record_value_with_count(value, weighted_count)
+    }
+
+    /// Decayed number of values recorded, as of `now`.
+    pub fn get_decayed_count_at(&self, now: Instant) -> f64 {
+        let weight_at_now = (1_u64 << LANDMARK_WEIGHT_MAGNITUDE) as f64 * self.half_lives_since_landmark(now).exp2();
+        self.histogram.get_total_count() as f64 / weight_at_now
+    }
+
+    pub fn get_value_at_percentile(&self, percentile: f64) -> u64 {
+        self.histogram.get_value_at_percentile(percentile)
+    }
+
+    pub fn get_values_at_percentiles(&self, percentiles: &[f64]) -> Vec<u64> {
+        self.histogram.get_values_at_percentiles(percentiles)
+    }
+
+    pub fn get_mean(&self) -> f64 {
+        self.histogram.get_mean()
+    }
+
+    pub fn get_std_deviation(&self) -> f64 {
+        self.histogram.get_std_deviation()
+    }
+
+    pub fn get_max_value(&self) -> u64 {
+        self.histogram.get_max_value()
+    }
+
+    pub fn get_min_value(&self) -> u64 {
+        self.histogram.get_min_value()
+    }
+
+    pub fn half_life(&self) -> Duration {
+        self.half_life
+    }
+
+    /// The underlying histogram. Its counts are weights relative to an internal landmark, so
+    /// only ratios between them are meaningful.
+    pub fn histogram(&self) -> &Histogram<u64> {
+        &self.histogram
+    }
+
+    pub fn reset(&mut self) {
+        self.histogram.reset();
+        self.landmark = Instant::now();
+    }
+
+    fn half_lives_since_landmark(&self, now: Instant) -> f64 {
+        now.saturating_duration_since(self.landmark).as_secs_f64() / self.half_life.as_secs_f64()
+    }
+
+    // Moves the landmark forward by `half_lives` and halves every count that many times, leaving
+    // less than a half-life between the landmark and `now`. Counts that decay to zero are dropped.
+    fn rescale(&mut self, half_lives: f64, now: Instant) {
+        if half_lives >= 64.0 {
+            // every count decays to zero
+            self.histogram.reset();
+            self.landmark = now;
+            return;
+        }
+        let half_lives = half_lives as u32;
+        self.landmark += self.half_life * half_lives;
+        let length = self.histogram.counts_array_length();
+        for count in self.histogram.get_counts_slice_mut(length).unwrap().iter_mut() {
+            *count >>= half_lives;
+        }
+        ConstructableHistogram::establish_internal_tracking_values(&mut self.histogram);
+    }
+}
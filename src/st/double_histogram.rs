@@ -1,4 +1,4 @@
-use crate::core::{DoubleCreationError, OverflowPolicy, RecordError, SaturateOnOverflow, ThrowOnOverflow};
+use crate::core::{DoubleCreationError, HistogramMetaData, OverflowPolicy, RecordError, SaturateOnOverflow, ThrowOnOverflow};
 use crate::iteration::RecordedValuesIterator;
 use crate::st::Histogram;
 use std::marker::PhantomData;
@@ -172,6 +172,15 @@ impl<P: OverflowPolicy> DoubleHistogramImpl<P> {
             * self.get_integer_to_double_value_conversion_ratio()
     }
 
+    pub fn get_values_at_percentiles(&self, percentiles: &[f64]) -> Vec<f64> {
+        let ratio = self.get_integer_to_double_value_conversion_ratio();
+        self.integer_histogram
+            .get_values_at_percentiles(percentiles)
+            .into_iter()
+            .map(|value| value as f64 * ratio)
+            .collect()
+    }
+
     pub fn get_percentile_at_or_below_value(&self, value: f64) -> f64 {
         let integer_value = self.to_integer_value_clamped(value);
         self.integer_histogram
@@ -241,6 +250,14 @@ impl<P: OverflowPolicy> DoubleHistogramImpl<P> {
         self.auto_resize
     }
 
+    pub fn meta_data(&self) -> &HistogramMetaData {
+        &self.integer_histogram.meta_data
+    }
+
+    pub fn meta_data_mut(&mut self) -> &mut HistogramMetaData {
+        &mut self.integer_histogram.meta_data
+    }
+
     pub fn reset(&mut self) {
         self.integer_histogram.reset();
         let initial_lowest_value_in_auto_range = 2.0_f64.powi(800);
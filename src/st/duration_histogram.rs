@@ -0,0 +1,133 @@
+use crate::core::*;
+use crate::iteration::*;
+use crate::st::Histogram;
+use std::time::Duration;
+
+/// A `Histogram` recording `Duration`s as integer counts of a fixed `TimeUnit`. The unit is stored
+/// in the histogram's metadata; durations are truncated to whole units when recorded.
+pub struct DurationHistogram<T: Counter> {
+    histogram: Histogram<T>,
+    time_unit: TimeUnit,
+}
+
+impl<T: Counter> DurationHistogram<T> {
+    /// Tracks durations in `[lowest_discernible, highest_trackable]`. The lowest discernible duration
+    /// is rounded up to at least one unit.
+    pub fn with_low_high_sigvdig(
+        time_unit: TimeUnit,
+        lowest_discernible: Duration,
+        highest_trackable: Duration,
+        significant_value_digits: u8,
+    ) -> Result<DurationHistogram<T>, CreationError> {
+        let histogram = Histogram::<T>::with_low_high_sigvdig(
+            time_unit.to_value(lowest_discernible).max(1),
+            time_unit.to_value(highest_trackable),
+            significant_value_digits,
+        )?;
+        Ok(DurationHistogram::from_histogram(histogram, time_unit))
+    }
+
+    /// Wraps a histogram whose values are already in `time_unit`.
+    pub fn from_histogram(mut histogram: Histogram<T>, time_unit: TimeUnit) -> DurationHistogram<T> {
+        histogram.meta_data.set_time_unit(time_unit);
+        DurationHistogram { histogram, time_unit }
+    }
+
+    pub fn time_unit(&self) -> TimeUnit {
+        self.time_unit
+    }
+
+    pub fn histogram(&self) -> &Histogram<T> {
+        &self.histogram
+    }
+
+    pub fn into_histogram(self) -> Histogram<T> {
+        self.histogram
+    }
+
+    pub fn record(&mut self, duration: Duration) -> Result<(), RecordError> {
+        self.histogram.record_value(self.time_unit.to_value(duration))
+    }
+
+    pub fn record_with_count(&mut self, duration: Duration, count: T) -> Result<(), RecordError> {
+        self.histogram
+            .record_value_with_count(self.time_unit.to_value(duration), count)
+    }
+
+    pub fn record_with_expected_interval(&mut self, duration: Duration, expected_interval: Duration) -> Result<(), RecordError> {
+        self.histogram.record_value_with_expected_interval(
+            self.time_unit.to_value(duration),
+            self.time_unit.to_value(expected_interval),
+        )
+    }
+
+    /// Fails without adding anything if `other` records in a different unit.
+    pub fn add(&mut self, other: &DurationHistogram<T>) -> Result<(), RecordError> {
+        if self.time_unit != other.time_unit {
+            return Err(RecordError::TimeUnitMismatch);
+        }
+        self.histogram.add(&other.histogram)
+    }
+
+    pub fn reset(&mut self) {
+        self.histogram.reset();
+    }
+
+    pub fn get_total_count(&self) -> u64 {
+        self.histogram.get_total_count()
+    }
+
+    pub fn value_at_percentile(&self, percentile: f64) -> Duration {
+        self.time_unit
+            .to_duration(self.histogram.get_value_at_percentile(percentile))
+    }
+
+    pub fn values_at_percentiles(&self, percentiles: &[f64]) -> Vec<Duration> {
+        self.histogram
+            .get_values_at_percentiles(percentiles)
+            .into_iter()
+            .map(|v| self.time_unit.to_duration(v))
+            .collect()
+    }
+
+    pub fn percentile_at_or_below(&self, duration: Duration) -> f64 {
+        self.histogram
+            .get_percentile_at_or_below_value(self.time_unit.to_value(duration))
+    }
+
+    pub fn min(&self) -> Duration {
+        self.time_unit.to_duration(self.histogram.get_min_value())
+    }
+
+    pub fn max(&self) -> Duration {
+        self.time_unit.to_duration(self.histogram.get_max_value())
+    }
+
+    pub fn mean(&self) -> Duration {
+        self.time_unit.to_duration_f64(self.histogram.get_mean())
+    }
+
+    pub fn std_deviation(&self) -> Duration {
+        self.time_unit
+            .to_duration_f64(self.histogram.get_std_deviation())
+    }
+
+    pub fn recorded_values(&self) -> DurationValues<RecordedValuesIterator<'_, Histogram<T>>> {
+        DurationValues::new(self.histogram.recorded_values(), self.time_unit)
+    }
+
+    pub fn percentiles(&self, percentile_ticks_per_half_distance: u32) -> DurationValues<PercentileIterator<'_, Histogram<T>>> {
+        DurationValues::new(
+            self.histogram.percentiles(percentile_ticks_per_half_distance),
+            self.time_unit,
+        )
+    }
+
+    pub fn linear_bucket_values(&self, duration_per_bucket: Duration) -> DurationValues<LinearIterator<'_, Histogram<T>>> {
+        DurationValues::new(
+            self.histogram
+                .linear_bucket_values(self.time_unit.to_value(duration_per_bucket).max(1)),
+            self.time_unit,
+        )
+    }
+}
@@ -1,5 +1,6 @@
 use crate::core::*;
 use crate::st::backing_array::BackingArray;
+use crate::st::indexed_histogram::IndexedHistogram;
 use crate::core::constants::*;
 use crate::core::counter::Counter;
 use crate::core::meta_data::HistogramMetaData;
@@ -152,36 +153,11 @@ impl<T: Counter> Histogram<T> {
     }
 
     pub fn get_value_at_percentile(&self, percentile: f64) -> u64 {
-        let one_below = util::next_below(percentile);
-        // please define some max min for partialord values already, ffs
-        let requested_percentile = if one_below > 100.0 {
-            100.0
-        } else if one_below < 0.0 {
-            0.0
-        } else {
-            one_below
-        };
-
-        let fractional_count = (requested_percentile / 100.0) * self.total_count as f64;
-        let mut count_at_percentile = fractional_count.ceil() as u64;
-
-        // Make sure we at least reach the first recorded entry
-        count_at_percentile = std::cmp::max(count_at_percentile, 1);
-
-        let mut total_to_current_index: u64 = 0;
-        for i in 0..self.counts_array_length() {
-            total_to_current_index += self.unsafe_get_count_at_index(i).as_u64();
-            if total_to_current_index >= count_at_percentile {
-                let value_at_index = self.value_from_index(i);
-                return if percentile == 0.0 {
-                    self.lowest_equivalent_value(value_at_index)
-                } else {
-                    self.highest_equivalent_value(value_at_index)
-                };
-            }
-        }
+        util::get_value_at_percentile(self, percentile)
+    }
 
-        0
+    pub fn get_values_at_percentiles(&self, percentiles: &[f64]) -> Vec<u64> {
+        util::get_values_at_percentiles(self, percentiles)
     }
 
     pub fn get_percentile_at_or_below_value(&self, value: u64) -> f64 {
@@ -607,6 +583,52 @@ impl<T: Counter> Histogram<T> {
         Ok(())
     }
 
+    /// Returns a copy of this histogram with `new_significant_value_digits` significant digits,
+    /// along with the count of values that could not be re-bucketed into it.
+    pub fn with_precision(&self, new_significant_value_digits: u8) -> Result<(Histogram<T>, u64), CreationError> {
+        self.rebucketed(
+            self.settings.lowest_discernible_value,
+            self.settings.highest_trackable_value,
+            new_significant_value_digits,
+        )
+    }
+
+    /// Returns a copy of this histogram covering `[lowest_discernible_value, highest_trackable_value]`,
+    /// along with the count of values that fell beyond the new trackable range and were dropped.
+    pub fn with_range(&self, lowest_discernible_value: u64, highest_trackable_value: u64) -> Result<(Histogram<T>, u64), CreationError> {
+        self.rebucketed(
+            lowest_discernible_value,
+            highest_trackable_value,
+            self.settings.number_of_significant_value_digits as u8,
+        )
+    }
+
+    fn rebucketed(
+        &self,
+        lowest_discernible_value: u64,
+        highest_trackable_value: u64,
+        significant_value_digits: u8,
+    ) -> Result<(Histogram<T>, u64), CreationError> {
+        let mut target = Histogram::<T>::with_low_high_sigvdig(
+            lowest_discernible_value,
+            highest_trackable_value,
+            significant_value_digits,
+        )?;
+        let mut dropped_count = 0_u64;
+        for i in 0..self.counts_array_length() {
+            let count = *self.unsafe_get_count_at_index(i);
+            if count != T::zero() && target.record_count_at_value_strict(count, self.value_from_index(i)).is_err() {
+                dropped_count += count.as_u64();
+            }
+        }
+        target.set_auto_resize(self.is_auto_resize());
+        target.meta_data.start_timestamp = self.meta_data.start_timestamp;
+        target.meta_data.end_timestamp = self.meta_data.end_timestamp;
+        target.meta_data.tag = self.meta_data.tag.clone();
+        target.meta_data.time_unit = self.meta_data.time_unit;
+        Ok((target, dropped_count))
+    }
+
     fn establish_internal_tracking_values(&mut self) {
         self.reset_max_value(ORIGINAL_MAX);
         self.reset_min_non_zero_value(ORIGINAL_MIN);
@@ -686,6 +708,12 @@ impl<T: Counter> Histogram<T> {
     pub fn recorded_values(&self) -> RecordedValuesIterator<'_, Self> {
         RecordedValuesIterator::new(self)
     }
+
+    /// Builds a cumulative count index for repeated percentile and rank queries against this
+    /// histogram. The index is a snapshot; the histogram can't be modified while it is borrowed.
+    pub fn indexed(&self) -> IndexedHistogram<'_, T> {
+        IndexedHistogram::new(self)
+    }
 }
 
 impl<T: Counter> ConstructableHistogram for Histogram<T> {
@@ -749,6 +777,32 @@ impl<T: Counter> Histogram<T> {
     }
 }
 
+impl Histogram<u64> {
+    /// An owned copy of any readable histogram, including its metadata.
+    pub(crate) fn copy_of<H: ReadableHistogram>(source: &H) -> Histogram<u64> {
+        let settings = source.settings();
+        let mut target = Histogram::<u64>::with_low_high_sigvdig(
+            settings.lowest_discernible_value,
+            settings.highest_trackable_value,
+            settings.number_of_significant_value_digits as u8,
+        ).unwrap();
+        target.set_auto_resize(settings.auto_resize);
+        for i in 0..source.array_length() {
+            let count = source.unsafe_get_count_at_index(i);
+            if count != 0 {
+                // the target covers the source's range, so this can't fail
+                let _ = target.record_value_with_count(settings.value_from_index(i), count);
+            }
+        }
+        let meta_data = source.meta_data();
+        target.meta_data.start_timestamp = meta_data.start_timestamp;
+        target.meta_data.end_timestamp = meta_data.end_timestamp;
+        target.meta_data.tag = meta_data.tag.clone();
+        target.meta_data.time_unit = meta_data.time_unit;
+        target
+    }
+}
+
 impl<T: Counter> PartialEq for Histogram<T> {
     fn eq(&self, other: &Self) -> bool {
         self.equals(other)
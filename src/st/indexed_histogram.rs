@@ -0,0 +1,79 @@
+use crate::core::counter::Counter;
+use crate::core::util;
+use crate::st::Histogram;
+use std::ops::Deref;
+
+/// A frozen view of a `Histogram` with a cumulative count index over its counts array, which
+/// makes percentile and rank queries logarithmic instead of linear in the array length.
+/// Create one via `Histogram::indexed`.
+pub struct IndexedHistogram<'a, T: 'a + Counter> {
+    histogram: &'a Histogram<T>,
+    // cumulative_counts[i] is the total count at logical indexes 0..=i
+    cumulative_counts: Vec<u64>,
+}
+
+impl<'a, T: Counter> Deref for IndexedHistogram<'a, T> {
+    type Target = Histogram<T>;
+    fn deref(&self) -> &Histogram<T> {
+        self.histogram
+    }
+}
+
+impl<'a, T: Counter> IndexedHistogram<'a, T> {
+    pub fn new(histogram: &'a Histogram<T>) -> IndexedHistogram<'a, T> {
+        let mut total = 0_u64;
+        let cumulative_counts = (0..histogram.counts_array_length())
+            .map(|i| {
+                total += histogram.unsafe_get_count_at_index(i).as_u64();
+                total
+            })
+            .collect();
+        IndexedHistogram {
+            histogram,
+            cumulative_counts,
+        }
+    }
+
+    pub fn get_value_at_percentile(&self, percentile: f64) -> u64 {
+        let count_at_percentile = util::count_at_percentile(self.histogram.get_total_count(), percentile);
+        let index = self
+            .cumulative_counts
+            .partition_point(|&total| total < count_at_percentile);
+        if index == self.cumulative_counts.len() {
+            0
+        } else {
+            util::value_at_percentile_index(self.histogram, index as u32, percentile)
+        }
+    }
+
+    pub fn get_values_at_percentiles(&self, percentiles: &[f64]) -> Vec<u64> {
+        percentiles
+            .iter()
+            .map(|&percentile| self.get_value_at_percentile(percentile))
+            .collect()
+    }
+
+    pub fn get_percentile_at_or_below_value(&self, value: u64) -> f64 {
+        let total_count = self.histogram.get_total_count();
+        if total_count == 0 {
+            return 100.0;
+        }
+        let target_index = self.histogram.saturating_counts_array_index(value);
+        (100.0 * self.cumulative_counts[target_index as usize] as f64) / total_count as f64
+    }
+
+    /// Total count of values in the range `[lowest_equivalent_value(low), highest_equivalent_value(high)]`.
+    pub fn get_count_between_values(&self, low_value: u64, high_value: u64) -> u64 {
+        let low_index = self.histogram.saturating_counts_array_index(low_value) as usize;
+        let high_index = self.histogram.saturating_counts_array_index(high_value) as usize;
+        if high_index < low_index {
+            return 0;
+        }
+        let below_low = if low_index == 0 {
+            0
+        } else {
+            self.cumulative_counts[low_index - 1]
+        };
+        self.cumulative_counts[high_index] - below_low
+    }
+}
@@ -1,6 +1,16 @@
 pub mod histogram;
 pub mod double_histogram;
 pub mod backing_array;
+pub mod indexed_histogram;
+pub mod decaying_histogram;
+pub mod duration_histogram;
+pub mod sliding_window_histogram;
+pub mod signed_histogram;
 
+pub use self::decaying_histogram::DecayingHistogram;
+pub use self::duration_histogram::DurationHistogram;
 pub use self::double_histogram::{DoubleHistogram, DoubleHistogramImpl, SaturatingDoubleHistogram};
 pub use self::histogram::Histogram;
+pub use self::indexed_histogram::IndexedHistogram;
+pub use self::signed_histogram::SignedHistogram;
+pub use self::sliding_window_histogram::SlidingWindowHistogram;
@@ -0,0 +1,260 @@
+use crate::core::*;
+use crate::core::util;
+use crate::st::Histogram;
+
+/// A histogram over `i64` values. Non-negative values are recorded into one `Histogram`,
+/// negative values by magnitude into a mirrored one built from the same settings.
+pub struct SignedHistogram<T: Counter> {
+    positive: Histogram<T>,
+    negative: Histogram<T>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SignedIterationValue {
+    /// Highest value equivalent to the bucket iterated to.
+    pub value_iterated_to: i64,
+    pub count_at_value_iterated_to: u64,
+    pub total_count_to_this_value: u64,
+    pub percentile: f64,
+}
+
+// -magnitude, saturating at i64::MIN
+fn negate(magnitude: u64) -> i64 {
+    if magnitude >= 1 << 63 {
+        i64::MIN
+    } else {
+        -(magnitude as i64)
+    }
+}
+
+fn saturate(value: u64) -> i64 {
+    if value > i64::MAX as u64 {
+        i64::MAX
+    } else {
+        value as i64
+    }
+}
+
+impl<T: Counter> SignedHistogram<T> {
+    /// Tracks values in `[-highest_trackable_value, highest_trackable_value]`, with magnitudes below
+    /// `lowest_discernible_value` indistinguishable from zero.
+    pub fn with_low_high_sigvdig(
+        lowest_discernible_value: u64,
+        highest_trackable_value: u64,
+        significant_value_digits: u8,
+    ) -> Result<SignedHistogram<T>, CreationError> {
+        let positive = Histogram::<T>::with_low_high_sigvdig(
+            lowest_discernible_value,
+            highest_trackable_value,
+            significant_value_digits,
+        )?;
+        let negative = Histogram::<T>::with_low_high_sigvdig(
+            lowest_discernible_value,
+            highest_trackable_value,
+            significant_value_digits,
+        )?;
+        Ok(SignedHistogram { positive, negative })
+    }
+
+    pub fn record_value(&mut self, value: i64) -> Result<(), RecordError> {
+        self.record_value_with_count(value, T::one())
+    }
+
+    pub fn record_value_with_count(&mut self, value: i64, count: T) -> Result<(), RecordError> {
+        if value < 0 {
+            self.negative.record_value_with_count(value.unsigned_abs(), count)
+        } else {
+            self.positive.record_value_with_count(value as u64, count)
+        }
+    }
+
+    pub fn add(&mut self, other: &SignedHistogram<T>) -> Result<(), RecordError> {
+        self.positive.add(&other.positive)?;
+        self.negative.add(&other.negative)
+    }
+
+    pub fn reset(&mut self) {
+        self.positive.reset();
+        self.negative.reset();
+    }
+
+    pub fn set_auto_resize(&mut self, auto_resize: bool) {
+        self.positive.set_auto_resize(auto_resize);
+        self.negative.set_auto_resize(auto_resize);
+    }
+
+    pub(crate) fn settings(&self) -> &HistogramSettings {
+        self.positive.settings()
+    }
+
+    /// Counts of the non-negative values.
+    pub fn positive(&self) -> &Histogram<T> {
+        &self.positive
+    }
+
+    /// Counts of the negative values, by magnitude.
+    pub fn negative(&self) -> &Histogram<T> {
+        &self.negative
+    }
+
+    pub fn get_total_count(&self) -> u64 {
+        self.positive.get_total_count() + self.negative.get_total_count()
+    }
+
+    pub fn get_count_at_value(&self, value: i64) -> Option<T> {
+        if value < 0 {
+            self.negative.get_count_at_value(value.unsigned_abs())
+        } else {
+            self.positive.get_count_at_value(value as u64)
+        }
+    }
+
+    pub fn get_min_value(&self) -> i64 {
+        if self.negative.get_total_count() != 0 {
+            negate(self.negative.get_max_value())
+        } else {
+            saturate(self.positive.get_min_value())
+        }
+    }
+
+    pub fn get_max_value(&self) -> i64 {
+        if self.positive.get_total_count() != 0 {
+            saturate(self.positive.get_max_value())
+        } else if self.negative.get_total_count() != 0 {
+            negate(self.negative.get_min_value())
+        } else {
+            0
+        }
+    }
+
+    pub fn get_mean(&self) -> f64 {
+        let total_count = self.get_total_count();
+        if total_count == 0 {
+            return 0.0;
+        }
+        let positive_total = self.positive.get_mean() * self.positive.get_total_count() as f64;
+        let negative_total = self.negative.get_mean() * self.negative.get_total_count() as f64;
+        (positive_total - negative_total) / total_count as f64
+    }
+
+    pub fn get_value_at_percentile(&self, percentile: f64) -> i64 {
+        let count_at_percentile = util::count_at_percentile(self.get_total_count(), percentile);
+        let negative_count = self.negative.get_total_count();
+        let settings = self.settings();
+        if count_at_percentile <= negative_count {
+            // negative values in ascending order are magnitudes in descending order
+            let mut total_to_current_index = 0;
+            for i in (0..self.negative.counts_array_length()).rev() {
+                total_to_current_index += ReadableHistogram::unsafe_get_count_at_index(&self.negative, i);
+                if total_to_current_index >= count_at_percentile {
+                    let magnitude = self.negative.value_from_index(i);
+                    return if percentile == 0.0 {
+                        negate(settings.highest_equivalent_value(magnitude))
+                    } else {
+                        negate(settings.lowest_equivalent_value(magnitude))
+                    };
+                }
+            }
+            0
+        } else {
+            let count_at_percentile = count_at_percentile - negative_count;
+            let mut total_to_current_index = 0;
+            for i in 0..self.positive.counts_array_length() {
+                total_to_current_index += ReadableHistogram::unsafe_get_count_at_index(&self.positive, i);
+                if total_to_current_index >= count_at_percentile {
+                    return saturate(util::value_at_percentile_index(&self.positive, i, percentile));
+                }
+            }
+            0
+        }
+    }
+
+    pub fn get_values_at_percentiles(&self, percentiles: &[f64]) -> Vec<i64> {
+        percentiles
+            .iter()
+            .map(|&percentile| self.get_value_at_percentile(percentile))
+            .collect()
+    }
+
+    pub fn get_percentile_at_or_below_value(&self, value: i64) -> f64 {
+        let total_count = self.get_total_count();
+        if total_count == 0 {
+            return 100.0;
+        }
+        let count_at_or_below = if value < 0 {
+            // negative values at or below `value` have magnitudes at or above its magnitude
+            let index = self.negative.saturating_counts_array_index(value.unsigned_abs());
+            (index..self.negative.counts_array_length())
+                .map(|i| ReadableHistogram::unsafe_get_count_at_index(&self.negative, i))
+                .sum::<u64>()
+        } else {
+            let index = self.positive.saturating_counts_array_index(value as u64);
+            self.negative.get_total_count()
+                + (0..=index)
+                    .map(|i| ReadableHistogram::unsafe_get_count_at_index(&self.positive, i))
+                    .sum::<u64>()
+        };
+        (100.0 * count_at_or_below as f64) / total_count as f64
+    }
+
+    /// Iterates the recorded buckets across the whole signed range in ascending value order.
+    pub fn recorded_values(&self) -> SignedRecordedValuesIterator<'_, T> {
+        SignedRecordedValuesIterator {
+            histogram: self,
+            negative_index: Some(self.negative.counts_array_length()),
+            positive_index: 0,
+            total_count_to_current_index: 0,
+        }
+    }
+}
+
+pub struct SignedRecordedValuesIterator<'a, T: 'a + Counter> {
+    histogram: &'a SignedHistogram<T>,
+    // the negative buckets are walked downwards from here before the positive ones
+    negative_index: Option<u32>,
+    positive_index: u32,
+    total_count_to_current_index: u64,
+}
+
+impl<'a, T: Counter> SignedRecordedValuesIterator<'a, T> {
+    fn value(&mut self, value_iterated_to: i64, count: u64) -> SignedIterationValue {
+        self.total_count_to_current_index += count;
+        SignedIterationValue {
+            value_iterated_to,
+            count_at_value_iterated_to: count,
+            total_count_to_this_value: self.total_count_to_current_index,
+            percentile: (100.0 * self.total_count_to_current_index as f64) / self.histogram.get_total_count() as f64,
+        }
+    }
+}
+
+impl<'a, T: Counter> Iterator for SignedRecordedValuesIterator<'a, T> {
+    type Item = SignedIterationValue;
+    fn next(&mut self) -> Option<SignedIterationValue> {
+        let settings = self.histogram.settings();
+        while let Some(index) = self.negative_index {
+            if index == 0 {
+                self.negative_index = None;
+                break;
+            }
+            let index = index - 1;
+            self.negative_index = Some(index);
+            let count = ReadableHistogram::unsafe_get_count_at_index(&self.histogram.negative, index);
+            if count != 0 {
+                let magnitude = settings.lowest_equivalent_value(settings.value_from_index(index));
+                return Some(self.value(negate(magnitude), count));
+            }
+        }
+        let positive = &self.histogram.positive;
+        while self.positive_index < positive.counts_array_length() {
+            let index = self.positive_index;
+            self.positive_index += 1;
+            let count = ReadableHistogram::unsafe_get_count_at_index(positive, index);
+            if count != 0 {
+                let value = settings.highest_equivalent_value(settings.value_from_index(index));
+                return Some(self.value(saturate(value), count));
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,132 @@
+use crate::core::*;
+use crate::st::Histogram;
+
+/// Keeps the last `interval_count` sub-interval histograms in a ring together with their sum, so
+/// queries over the whole window cost the same regardless of how many intervals it spans.
+/// Call `rotate` on every clock tick to evict the oldest interval.
+pub struct SlidingWindowHistogram<T: Counter> {
+    intervals: Vec<Histogram<T>>,
+    current_interval: usize,
+    aggregate: Histogram<T>,
+}
+
+impl<T: Counter> SlidingWindowHistogram<T> {
+    pub fn with_low_high_sigvdig(
+        interval_count: usize,
+        lowest_discernible_value: u64,
+        highest_trackable_value: u64,
+        significant_value_digits: u8,
+    ) -> Result<SlidingWindowHistogram<T>, SlidingWindowCreationError> {
+        if interval_count == 0 {
+            return Err(SlidingWindowCreationError::IntervalCountIsZero);
+        }
+        let new_histogram = || {
+            Histogram::<T>::with_low_high_sigvdig(
+                lowest_discernible_value,
+                highest_trackable_value,
+                significant_value_digits,
+            )
+            .map_err(SlidingWindowCreationError::Internal)
+        };
+        let intervals = (0..interval_count)
+            .map(|_| new_histogram())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(SlidingWindowHistogram {
+            intervals,
+            current_interval: 0,
+            aggregate: new_histogram()?,
+        })
+    }
+
+    pub fn record_value(&mut self, value: u64) -> Result<(), RecordError> {
+        self.record_value_with_count(value, T::one())
+    }
+
+    pub fn record_value_with_count(&mut self, value: u64, count: T) -> Result<(), RecordError> {
+        self.intervals[self.current_interval].record_value_with_count(value, count)?;
+        self.aggregate.record_value_with_count(value, count)
+    }
+
+    /// Starts a new interval, dropping the oldest one from the window. On error the window is
+    /// left unchanged.
+    pub fn rotate(&mut self) -> Result<(), SubtractionError> {
+        let next_interval = (self.current_interval + 1) % self.intervals.len();
+        let evicted = &self.intervals[next_interval];
+        if evicted.get_total_count() != 0 {
+            // `subtract` can fail halfway through, so check every count before changing anything
+            for i in 0..evicted.counts_array_length() {
+                let count = evicted.get_count_at_index(i).copied().unwrap_or_else(T::zero);
+                if count == T::zero() {
+                    continue;
+                }
+                let aggregate_count = self.aggregate.get_count_at_value(evicted.value_from_index(i));
+                if aggregate_count.unwrap_or_else(T::zero) < count {
+                    return Err(SubtractionError::CountExceededAtValue);
+                }
+            }
+            self.aggregate.subtract(evicted)?;
+            self.intervals[next_interval].reset();
+        }
+        self.current_interval = next_interval;
+        Ok(())
+    }
+
+    /// The sum of all intervals in the window.
+    pub fn aggregate(&self) -> &Histogram<T> {
+        &self.aggregate
+    }
+
+    /// The interval currently being recorded into.
+    pub fn current_interval(&self) -> &Histogram<T> {
+        &self.intervals[self.current_interval]
+    }
+
+    pub fn interval_count(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn get_total_count(&self) -> u64 {
+        self.aggregate.get_total_count()
+    }
+
+    pub fn get_value_at_percentile(&self, percentile: f64) -> u64 {
+        self.aggregate.get_value_at_percentile(percentile)
+    }
+
+    pub fn get_values_at_percentiles(&self, percentiles: &[f64]) -> Vec<u64> {
+        self.aggregate.get_values_at_percentiles(percentiles)
+    }
+
+    pub fn get_mean(&self) -> f64 {
+        self.aggregate.get_mean()
+    }
+
+    pub fn get_max_value(&self) -> u64 {
+        self.aggregate.get_max_value()
+    }
+
+    pub fn reset(&mut self) {
+        for interval in self.intervals.iter_mut() {
+            interval.reset();
+        }
+        self.aggregate.reset();
+        self.current_interval = 0;
+    }
+}
+
+impl SlidingWindowHistogram<u64> {
+    /// Rotates the window and fills the new interval with the counts of `sample`. Values beyond
+    /// the window's range are clamped into it as with `record_value`, so this only fails if the
+    /// rotation does, in which case the window is left unchanged.
+    pub fn push_interval<H: ReadableHistogram>(&mut self, sample: &H) -> Result<(), RecordError> {
+        self.rotate()?;
+        let settings = sample.settings();
+        for i in 0..sample.array_length() {
+            let count = sample.unsafe_get_count_at_index(i);
+            if count != 0 {
+                self.record_value_with_count(settings.value_from_index(i), count)?;
+            }
+        }
+        Ok(())
+    }
+}
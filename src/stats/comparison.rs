@@ -0,0 +1,177 @@
+use crate::core::ReadableHistogram;
+use crate::core::util;
+
+/// Two-sample Kolmogorov–Smirnov test result.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KolmogorovSmirnov {
+    /// Largest absolute difference between the two cumulative distributions.
+    pub statistic: f64,
+    /// Asymptotic probability of observing a statistic at least this large if both histograms
+    /// were sampled from the same distribution.
+    pub p_value: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PercentileRatio {
+    pub percentile: f64,
+    pub baseline: u64,
+    pub candidate: u64,
+    /// `candidate / baseline`; `1.0` if both are zero and infinite if only the baseline is.
+    pub ratio: f64,
+}
+
+// Walks the recorded buckets of both histograms in value order. For every distinct bucket upper
+// bound (its highest equivalent value) `f` receives that value and the fraction of each
+// histogram's total count at or below it.
+fn walk_cdfs<A: ReadableHistogram, B: ReadableHistogram, F: FnMut(u64, f64, f64)>(a: &A, b: &B, mut f: F) {
+    let a_total = a.get_total_count() as f64;
+    let b_total = b.get_total_count() as f64;
+    let mut a_cursor = BucketCursor::new(a);
+    let mut b_cursor = BucketCursor::new(b);
+    let mut a_cumulative = 0_u64;
+    let mut b_cumulative = 0_u64;
+    loop {
+        let value = match (a_cursor.peek(a), b_cursor.peek(b)) {
+            (None, None) => return,
+            (Some((va, _)), None) => va,
+            (None, Some((vb, _))) => vb,
+            (Some((va, _)), Some((vb, _))) => std::cmp::min(va, vb),
+        };
+        if let Some((va, count)) = a_cursor.peek(a) {
+            if va == value {
+                a_cumulative += count;
+                a_cursor.advance();
+            }
+        }
+        if let Some((vb, count)) = b_cursor.peek(b) {
+            if vb == value {
+                b_cumulative += count;
+                b_cursor.advance();
+            }
+        }
+        f(value, a_cumulative as f64 / a_total, b_cumulative as f64 / b_total);
+    }
+}
+
+struct BucketCursor {
+    index: u32,
+}
+
+impl BucketCursor {
+    fn new<H: ReadableHistogram>(histogram: &H) -> BucketCursor {
+        let mut cursor = BucketCursor { index: 0 };
+        cursor.skip_empty(histogram);
+        cursor
+    }
+
+    fn skip_empty<H: ReadableHistogram>(&mut self, histogram: &H) {
+        while self.index < histogram.array_length() && histogram.unsafe_get_count_at_index(self.index) == 0 {
+            self.index += 1;
+        }
+    }
+
+    fn peek<H: ReadableHistogram>(&mut self, histogram: &H) -> Option<(u64, u64)> {
+        self.skip_empty(histogram);
+        if self.index >= histogram.array_length() {
+            return None;
+        }
+        let settings = histogram.settings();
+        let value = settings.highest_equivalent_value(settings.value_from_index(self.index));
+        Some((value, histogram.unsafe_get_count_at_index(self.index)))
+    }
+
+    fn advance(&mut self) {
+        self.index += 1;
+    }
+}
+
+// Survival function of the Kolmogorov distribution, Q(lambda) = 2 sum_j (-1)^(j-1) exp(-2 j^2 lambda^2)
+fn kolmogorov_survival(lambda: f64) -> f64 {
+    let exponent_factor = -2.0 * lambda * lambda;
+    let mut sign = 2.0;
+    let mut sum = 0.0;
+    let mut previous_term = 0.0;
+    for j in 1..=100 {
+        let j = j as f64;
+        let term = sign * (exponent_factor * j * j).exp();
+        sum += term;
+        if term.abs() <= 0.001 * previous_term || term.abs() <= 1.0e-8 * sum {
+            return sum.clamp(0.0, 1.0);
+        }
+        sign = -sign;
+        previous_term = term.abs();
+    }
+    // the series doesn't converge for very small lambda, where the distributions are indistinguishable
+    1.0
+}
+
+/// Two-sample Kolmogorov–Smirnov statistic and its asymptotic p-value. Both histograms are
+/// compared at the upper bounds of their recorded buckets. `None` if either histogram is empty,
+/// as there is nothing to compare.
+pub fn kolmogorov_smirnov<A: ReadableHistogram, B: ReadableHistogram>(a: &A, b: &B) -> Option<KolmogorovSmirnov> {
+    let a_total = a.get_total_count();
+    let b_total = b.get_total_count();
+    if a_total == 0 || b_total == 0 {
+        return None;
+    }
+
+    let mut statistic = 0.0_f64;
+    walk_cdfs(a, b, |_, fa, fb| statistic = statistic.max((fa - fb).abs()));
+
+    let effective_count = (a_total as f64 * b_total as f64) / (a_total as f64 + b_total as f64);
+    let root = effective_count.sqrt();
+    let p_value = kolmogorov_survival((root + 0.12 + 0.11 / root) * statistic);
+    Some(KolmogorovSmirnov { statistic, p_value })
+}
+
+/// Wasserstein (earth mover's) distance between the two distributions, in value units. Each
+/// bucket's count is placed at the bucket's highest equivalent value. `None` if either histogram
+/// is empty.
+pub fn wasserstein_distance<A: ReadableHistogram, B: ReadableHistogram>(a: &A, b: &B) -> Option<f64> {
+    if a.get_total_count() == 0 || b.get_total_count() == 0 {
+        return None;
+    }
+
+    let mut distance = 0.0;
+    let mut previous_value = 0_u64;
+    let mut previous_difference = 0.0_f64;
+    walk_cdfs(a, b, |value, fa, fb| {
+        distance += previous_difference * (value - previous_value) as f64;
+        previous_value = value;
+        previous_difference = (fa - fb).abs();
+    });
+    Some(distance)
+}
+
+/// Value at each of `percentiles` in the baseline and candidate histograms, with their ratio.
+/// `None` if either histogram is empty.
+pub fn percentile_ratios<A: ReadableHistogram, B: ReadableHistogram>(
+    baseline: &A,
+    candidate: &B,
+    percentiles: &[f64],
+) -> Option<Vec<PercentileRatio>> {
+    if baseline.get_total_count() == 0 || candidate.get_total_count() == 0 {
+        return None;
+    }
+
+    let baseline_values = util::get_values_at_percentiles(baseline, percentiles);
+    let candidate_values = util::get_values_at_percentiles(candidate, percentiles);
+    let ratios = percentiles
+        .iter()
+        .zip(baseline_values.into_iter().zip(candidate_values))
+        .map(|(&percentile, (baseline_value, candidate_value))| {
+            let ratio = if baseline_value == candidate_value {
+                1.0
+            } else {
+                candidate_value as f64 / baseline_value as f64
+            };
+            PercentileRatio {
+                percentile,
+                baseline: baseline_value,
+                candidate: candidate_value,
+                ratio,
+            }
+        })
+        .collect();
+    Some(ratios)
+}
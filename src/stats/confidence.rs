@@ -0,0 +1,145 @@
+use crate::core::ReadableHistogram;
+use crate::core::util;
+
+/// The value reported for a percentile together with the range of values the histogram can't
+/// tell apart from it. Any difference inside this range is below the histogram's resolution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PercentileBounds {
+    pub percentile: f64,
+    pub value: u64,
+    pub lowest_equivalent_value: u64,
+    pub highest_equivalent_value: u64,
+}
+
+/// A binomial confidence interval for a percentile, given that the histogram holds
+/// `total_count` samples of the underlying distribution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PercentileConfidenceInterval {
+    pub bounds: PercentileBounds,
+    pub confidence: f64,
+    pub lower_rank: u64,
+    pub upper_rank: u64,
+    /// Lowest equivalent value of the value at `lower_rank`.
+    pub lower_value: u64,
+    /// Highest equivalent value of the value at `upper_rank`.
+    pub upper_value: u64,
+}
+
+// Inverse of the standard normal CDF, using Acklam's rational approximation (relative error < 1.2e-9).
+fn inverse_normal_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.38357751867269e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    if p < P_LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -inverse_normal_cdf(1.0 - p)
+    }
+}
+
+// Logical index of the bucket holding the value of the given 1-based rank.
+fn index_at_rank<H: ReadableHistogram>(histogram: &H, rank: u64) -> u32 {
+    let mut total_to_current_index = 0;
+    for i in 0..histogram.array_length() {
+        total_to_current_index += histogram.unsafe_get_count_at_index(i);
+        if total_to_current_index >= rank {
+            return i;
+        }
+    }
+    0
+}
+
+/// The value at `percentile` and the range of values equivalent to it.
+pub fn percentile_bounds<H: ReadableHistogram>(histogram: &H, percentile: f64) -> PercentileBounds {
+    let settings = histogram.settings();
+    let value = util::get_value_at_percentile(histogram, percentile);
+    PercentileBounds {
+        percentile,
+        value,
+        lowest_equivalent_value: settings.lowest_equivalent_value(value),
+        highest_equivalent_value: settings.highest_equivalent_value(value),
+    }
+}
+
+/// Confidence interval for the value at `percentile` at the given `confidence` level (e.g. `0.95`).
+/// The rank interval uses the normal approximation to the binomial distribution of the number of
+/// samples below the true percentile; the value interval is widened to whole buckets.
+pub fn percentile_confidence_interval<H: ReadableHistogram>(
+    histogram: &H,
+    percentile: f64,
+    confidence: f64,
+) -> PercentileConfidenceInterval {
+    let bounds = percentile_bounds(histogram, percentile);
+    let total_count = histogram.get_total_count();
+    if total_count == 0 {
+        return PercentileConfidenceInterval {
+            bounds,
+            confidence,
+            lower_rank: 0,
+            upper_rank: 0,
+            lower_value: bounds.lowest_equivalent_value,
+            upper_value: bounds.highest_equivalent_value,
+        };
+    }
+
+    let n = total_count as f64;
+    let p = percentile.clamp(0.0, 100.0) / 100.0;
+    let z = inverse_normal_cdf(0.5 + confidence.clamp(0.0, 1.0) / 2.0);
+    let expected_rank = n * p;
+    let rank_deviation = z * (n * p * (1.0 - p)).sqrt();
+    let lower_rank = ((expected_rank - rank_deviation).floor().max(1.0) as u64).min(total_count);
+    let upper_rank = ((expected_rank + rank_deviation).ceil().max(1.0) as u64).min(total_count);
+
+    let settings = histogram.settings();
+    let lower_value = settings.lowest_equivalent_value(settings.value_from_index(index_at_rank(histogram, lower_rank)));
+    let upper_value = settings.highest_equivalent_value(settings.value_from_index(index_at_rank(histogram, upper_rank)));
+    PercentileConfidenceInterval {
+        bounds,
+        confidence,
+        lower_rank,
+        upper_rank,
+        lower_value,
+        upper_value,
+    }
+}
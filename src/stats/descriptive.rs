@@ -0,0 +1,153 @@
+use crate::core::ReadableHistogram;
+use crate::core::util;
+
+// Calls `f` with the median equivalent value and count of every recorded bucket, in value order.
+fn for_each_bucket<H: ReadableHistogram, F: FnMut(u64, u64)>(histogram: &H, mut f: F) {
+    let settings = histogram.settings();
+    for i in 0..histogram.array_length() {
+        let count = histogram.unsafe_get_count_at_index(i);
+        if count != 0 {
+            f(settings.median_equivalent_value(settings.value_from_index(i)), count);
+        }
+    }
+}
+
+fn clamp_percentile(percentile: f64) -> f64 {
+    percentile.clamp(0.0, 100.0)
+}
+
+// Mean and the second, third and fourth central moments.
+fn moments<H: ReadableHistogram>(histogram: &H) -> (f64, f64, f64, f64) {
+    let total_count = histogram.get_total_count() as f64;
+    let mut total_value = 0.0;
+    for_each_bucket(histogram, |value, count| total_value += value as f64 * count as f64);
+    let mean = total_value / total_count;
+
+    let (mut m2, mut m3, mut m4) = (0.0, 0.0, 0.0);
+    for_each_bucket(histogram, |value, count| {
+        let deviation = value as f64 - mean;
+        let squared = deviation * deviation;
+        m2 += squared * count as f64;
+        m3 += squared * deviation * count as f64;
+        m4 += squared * squared * count as f64;
+    });
+    (mean, m2 / total_count, m3 / total_count, m4 / total_count)
+}
+
+/// Mean of the values ranked between `low_percentile` and `high_percentile`. Buckets straddling
+/// either boundary contribute only the part of their count inside it.
+pub fn trimmed_mean<H: ReadableHistogram>(histogram: &H, low_percentile: f64, high_percentile: f64) -> f64 {
+    let total_count = histogram.get_total_count() as f64;
+    let low_rank = total_count * clamp_percentile(low_percentile) / 100.0;
+    let high_rank = total_count * clamp_percentile(high_percentile) / 100.0;
+    let mut count_to_current_bucket = 0.0;
+    let mut total_value = 0.0;
+    let mut total_weight = 0.0;
+    for_each_bucket(histogram, |value, count| {
+        let bucket_start = count_to_current_bucket;
+        count_to_current_bucket += count as f64;
+        let weight = count_to_current_bucket.min(high_rank) - bucket_start.max(low_rank);
+        if weight > 0.0 {
+            total_value += weight * value as f64;
+            total_weight += weight;
+        }
+    });
+    if total_weight == 0.0 {
+        0.0
+    } else {
+        total_value / total_weight
+    }
+}
+
+/// Mean with values below `low_percentile` and above `high_percentile` replaced by the values at
+/// those percentiles.
+pub fn winsorized_mean<H: ReadableHistogram>(histogram: &H, low_percentile: f64, high_percentile: f64) -> f64 {
+    let total_count = histogram.get_total_count();
+    if total_count == 0 {
+        return 0.0;
+    }
+    let settings = histogram.settings();
+    let bounds = util::get_values_at_percentiles(histogram, &[low_percentile, high_percentile]);
+    let low = settings.median_equivalent_value(bounds[0]) as f64;
+    let high = settings.median_equivalent_value(bounds[1]) as f64;
+    let mut total_value = 0.0;
+    for_each_bucket(histogram, |value, count| {
+        total_value += (value as f64).max(low).min(high) * count as f64
+    });
+    total_value / total_count as f64
+}
+
+/// Median of the absolute deviations from the median.
+pub fn median_absolute_deviation<H: ReadableHistogram>(histogram: &H) -> f64 {
+    let total_count = histogram.get_total_count();
+    if total_count == 0 {
+        return 0.0;
+    }
+    let settings = histogram.settings();
+    let median = settings.median_equivalent_value(util::get_value_at_percentile(histogram, 50.0)) as f64;
+    let mut deviations = Vec::new();
+    for_each_bucket(histogram, |value, count| deviations.push(((value as f64 - median).abs(), count)));
+    deviations.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let count_at_median = total_count.div_ceil(2);
+    let mut count_to_current_deviation = 0;
+    for (deviation, count) in deviations {
+        count_to_current_deviation += count;
+        if count_to_current_deviation >= count_at_median {
+            return deviation;
+        }
+    }
+    0.0
+}
+
+/// Population skewness, the third standardized moment.
+pub fn skewness<H: ReadableHistogram>(histogram: &H) -> f64 {
+    if histogram.get_total_count() == 0 {
+        return 0.0;
+    }
+    let (_, m2, m3, _) = moments(histogram);
+    if m2 == 0.0 {
+        0.0
+    } else {
+        m3 / m2.powf(1.5)
+    }
+}
+
+/// Population kurtosis, the fourth standardized moment. A normal distribution has a kurtosis of 3.
+pub fn kurtosis<H: ReadableHistogram>(histogram: &H) -> f64 {
+    if histogram.get_total_count() == 0 {
+        return 0.0;
+    }
+    let (_, m2, _, m4) = moments(histogram);
+    if m2 == 0.0 {
+        0.0
+    } else {
+        m4 / (m2 * m2)
+    }
+}
+
+/// Median equivalent value of the bucket with the highest count. Ties go to the lowest bucket.
+pub fn mode<H: ReadableHistogram>(histogram: &H) -> Option<u64> {
+    let mut modal_bucket = None;
+    let mut modal_count = 0;
+    for_each_bucket(histogram, |value, count| {
+        if count > modal_count {
+            modal_count = count;
+            modal_bucket = Some(value);
+        }
+    });
+    modal_bucket
+}
+
+/// Standard deviation divided by the mean.
+pub fn coefficient_of_variation<H: ReadableHistogram>(histogram: &H) -> f64 {
+    if histogram.get_total_count() == 0 {
+        return 0.0;
+    }
+    let (mean, m2, _, _) = moments(histogram);
+    if mean == 0.0 {
+        0.0
+    } else {
+        m2.sqrt() / mean
+    }
+}
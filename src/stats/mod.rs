@@ -0,0 +1,6 @@
+pub mod comparison;
+pub mod confidence;
+pub mod descriptive;
+
+pub use self::comparison::{KolmogorovSmirnov, PercentileRatio};
+pub use self::confidence::{PercentileBounds, PercentileConfidenceInterval};
@@ -0,0 +1,63 @@
+use crate::st::Histogram;
+use crate::stats::comparison::*;
+use crate::tests::util::*;
+
+#[test]
+fn identical_histograms_are_indistinguishable() {
+    let a = raw_stat_histo();
+    let b = raw_stat_histo();
+    let ks = kolmogorov_smirnov(&a, &b).unwrap();
+    assert_eq!(0.0, ks.statistic);
+    assert_approx_eq!(ks.p_value, 1.0, 0.000001);
+    assert_eq!(Some(0.0), wasserstein_distance(&a, &b));
+}
+
+#[test]
+fn shifted_histograms_differ() {
+    let a = uniform_histogram(1000, 1999, 3);
+    let b = uniform_histogram(1500, 2499, 3);
+    let ks = kolmogorov_smirnov(&a, &b).unwrap();
+    assert_approx_eq!(ks.statistic, 0.5, 0.01);
+    assert!(ks.p_value < 0.0001);
+    assert_approx_eq!(wasserstein_distance(&a, &b).unwrap(), 500.0, 5.0);
+    assert_approx_eq!(wasserstein_distance(&b, &a).unwrap(), 500.0, 5.0);
+}
+
+#[test]
+fn comparison_across_settings() {
+    let a = uniform_histogram(1000, 1999, 3);
+    let mut b = Histogram::<u64>::with_low_high_sigvdig(1, 1000 * 1000, 2).unwrap();
+    for v in 1000..2000 {
+        succ!(b.record_value(v));
+    }
+    let ks = kolmogorov_smirnov(&a, &b).unwrap();
+    assert!(ks.statistic < 0.02);
+    assert!(ks.p_value > 0.5);
+    assert!(wasserstein_distance(&a, &b).unwrap() < 10.0);
+}
+
+#[test]
+fn empty_histograms_are_not_comparable() {
+    let empty = Histogram::<u64>::new(3).unwrap();
+    let recorded = raw_stat_histo();
+    assert_eq!(None, kolmogorov_smirnov(&empty, &recorded));
+    assert_eq!(None, kolmogorov_smirnov(&recorded, &empty));
+    assert_eq!(None, kolmogorov_smirnov(&empty, &empty));
+    assert_eq!(None, wasserstein_distance(&empty, &recorded));
+    assert_eq!(None, wasserstein_distance(&recorded, &empty));
+    assert_eq!(None, percentile_ratios(&empty, &recorded, &[50.0]));
+    assert_eq!(None, percentile_ratios(&recorded, &empty, &[50.0]));
+}
+
+#[test]
+fn percentile_ratio_table() {
+    let baseline = uniform_histogram(1000, 1999, 3);
+    let candidate = uniform_histogram(2000, 3999, 3);
+    let ratios = percentile_ratios(&baseline, &candidate, &[50.0, 99.0]).unwrap();
+    assert_eq!(2, ratios.len());
+    for r in ratios.iter() {
+        assert_eq!(baseline.get_value_at_percentile(r.percentile), r.baseline);
+        assert_eq!(candidate.get_value_at_percentile(r.percentile), r.candidate);
+        assert_approx_eq!(r.ratio, 2.0, 0.01);
+    }
+}
@@ -1,9 +1,11 @@
-use crate::concurrent::resizable_histogram::ResizableHistogram;
+use crate::concurrent::resizable_histogram::{ResizableHistogram, ResizableHistogram16, ResizableHistogram32};
+use crate::concurrent::static_histogram::{StaticHistogram, StaticHistogramImpl};
+use crate::core::{ReadableHistogram, RecordError};
 use parking_lot::RwLock;
 use rand::rngs::StdRng;
 use std::sync::Arc;
 use std::sync::Barrier;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::thread;
 use rand::{Rng, SeedableRng};
 
@@ -124,3 +126,89 @@ fn concurrent_auto_sized_recording() {
     let histogram = shared.read().clone();
     assert_eq!(sum, histogram.get_total_count());
 }
+
+#[test]
+fn total_count_while_resizing() {
+    let histogram = new_auto_resize_histogram();
+    for value in 1..=1000 {
+        succ!(histogram.record_value(value));
+    }
+    let resizer = {
+        let histogram = Arc::clone(&histogram);
+        thread::spawn(move || {
+            for shift in 12..40 {
+                succ!(histogram.resize(1_u64 << shift));
+            }
+        })
+    };
+    while !resizer.is_finished() {
+        assert_eq!(1000, histogram.get_total_count());
+    }
+    resizer.join().unwrap();
+    assert_eq!(1000, histogram.get_total_count());
+}
+
+#[test]
+fn static_histogram_with_computed_length() {
+    const HIGHEST_TRACKABLE: u64 = 3600 * 1000 * 1000;
+    let histogram: crate::static_histogram!(1, HIGHEST_TRACKABLE, 3) =
+        StaticHistogram::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap();
+    succ!(histogram.record_value(HIGHEST_TRACKABLE));
+    assert_eq!(histogram.get_total_count(), 1);
+}
+
+#[test]
+fn narrow_counters_reject_overflowing_counts() {
+    let histogram = ResizableHistogram16::with_low_high_sigvdig(1, 1000, 2).unwrap();
+    succ!(histogram.record_value_with_count(10, u16::MAX as u64));
+    assert_eq!(histogram.record_value(10), Err(RecordError::CountOverflow));
+    assert_eq!(
+        histogram.record_value_with_count(20, u16::MAX as u64 + 1),
+        Err(RecordError::CountOverflow)
+    );
+    succ!(histogram.record_value(20));
+    let index = histogram.settings().counts_array_index(10);
+    assert_eq!(histogram.get_count_at_index(index), Some(u16::MAX as u64));
+    assert_eq!(histogram.get_total_count(), u16::MAX as u64 + 1);
+}
+
+#[test]
+fn concurrent_record_values_with_32_bit_counters() {
+    const THREAD_COUNT: u64 = 4;
+    const NUM_VALS: u64 = 100000;
+    let histogram = Arc::new(ResizableHistogram32::new(2).unwrap());
+    let handles = (0..THREAD_COUNT)
+        .map(|tid| {
+            let histogram = histogram.clone();
+            thread::spawn(move || {
+                let mut rng = StdRng::seed_from_u64(tid);
+                for _ in 0..NUM_VALS {
+                    succ!(histogram.record_value(rng.gen_range(1_u64..(1_u64 << 40))));
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(histogram.get_total_count(), THREAD_COUNT * NUM_VALS);
+}
+
+#[test]
+fn static_histogram_with_32_bit_counters() {
+    const HIGHEST_TRACKABLE: u64 = 3600 * 1000 * 1000;
+    let narrow: crate::static_histogram!(1, HIGHEST_TRACKABLE, 3, AtomicU32) =
+        StaticHistogramImpl::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap();
+    let wide: crate::static_histogram!(1, HIGHEST_TRACKABLE, 3) =
+        StaticHistogram::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap();
+    for value in (1..HIGHEST_TRACKABLE).step_by(999_983) {
+        succ!(narrow.record_value(value));
+        succ!(wide.record_value(value));
+    }
+    assert_eq!(narrow.get_total_count(), wide.get_total_count());
+    let narrow = unsafe { narrow.unsafe_as_snapshot() };
+    let wide = unsafe { wide.unsafe_as_snapshot() };
+    for percentile in [50.0, 90.0, 99.0, 99.9] {
+        assert_eq!(narrow.get_value_at_percentile(percentile), wide.get_value_at_percentile(percentile));
+    }
+}
@@ -0,0 +1,50 @@
+use crate::st::Histogram;
+use crate::stats::confidence::*;
+use crate::tests::util::*;
+
+#[test]
+fn percentile_bounds_cover_quantization() {
+    let histogram = raw_stat_histo();
+    let bounds = percentile_bounds(&histogram, 99.999);
+    assert_eq!(histogram.get_value_at_percentile(99.999), bounds.value);
+    assert_eq!(histogram.lowest_equivalent_value(100000000), bounds.lowest_equivalent_value);
+    assert_eq!(histogram.highest_equivalent_value(100000000), bounds.highest_equivalent_value);
+    assert!(bounds.lowest_equivalent_value <= 100000000 && 100000000 <= bounds.highest_equivalent_value);
+}
+
+#[test]
+fn median_confidence_interval() {
+    let histogram = uniform_histogram(1, 10000, 2);
+    let interval = percentile_confidence_interval(&histogram, 50.0, 0.95);
+    // 1.96 * sqrt(10000 * 0.5 * 0.5) = 98
+    assert_eq!(5000 - 98, interval.lower_rank);
+    assert_eq!(5000 + 98, interval.upper_rank);
+    assert!(interval.lower_value <= histogram.lowest_equivalent_value(5000 - 98));
+    assert!(interval.upper_value >= histogram.highest_equivalent_value(5000 + 98));
+    assert!(interval.lower_value <= interval.bounds.lowest_equivalent_value);
+    assert!(interval.upper_value >= interval.bounds.highest_equivalent_value);
+
+    let wider = percentile_confidence_interval(&histogram, 50.0, 0.999);
+    assert!(wider.lower_rank < interval.lower_rank);
+    assert!(wider.upper_rank > interval.upper_rank);
+}
+
+#[test]
+fn extreme_percentile_interval_is_clamped() {
+    let histogram = uniform_histogram(1, 100, 2);
+    let interval = percentile_confidence_interval(&histogram, 100.0, 0.95);
+    assert_eq!(100, interval.lower_rank);
+    assert_eq!(100, interval.upper_rank);
+    let interval = percentile_confidence_interval(&histogram, 0.0, 0.95);
+    assert_eq!(1, interval.lower_rank);
+    assert_eq!(1, interval.upper_rank);
+}
+
+#[test]
+fn empty_histogram_interval() {
+    let histogram = Histogram::<u64>::new(3).unwrap();
+    let interval = percentile_confidence_interval(&histogram, 99.0, 0.95);
+    assert_eq!(0, interval.bounds.value);
+    assert_eq!(0, interval.lower_rank);
+    assert_eq!(0, interval.upper_rank);
+}
@@ -0,0 +1,91 @@
+use crate::core::*;
+use crate::st::DecayingHistogram;
+use std::time::{Duration, Instant};
+
+const HALF_LIFE: Duration = Duration::from_secs(10);
+
+fn new_histogram() -> (DecayingHistogram, Instant) {
+    let histogram = DecayingHistogram::new(3600 * 1000 * 1000, 3, HALF_LIFE).unwrap();
+    (histogram, Instant::now())
+}
+
+#[test]
+fn recent_values_outweigh_old_ones() {
+    let (mut histogram, start) = new_histogram();
+    for _ in 0..1000 {
+        succ!(histogram.record_value_at(100, start));
+    }
+    let later = start + HALF_LIFE * 3;
+    for _ in 0..200 {
+        succ!(histogram.record_value_at(10000, later));
+    }
+
+    // old values weigh 1000 / 8 = 125 against 200 recent ones
+    assert_approx_eq!(histogram.get_decayed_count_at(later), 325.0, 1.0);
+    assert!(histogram.histogram().values_are_equivalent(10000, histogram.get_value_at_percentile(50.0)));
+    assert!(histogram.histogram().values_are_equivalent(100, histogram.get_value_at_percentile(30.0)));
+    let expected_mean = (125.0 * 100.0 + 200.0 * 10000.0) / 325.0;
+    assert_approx_eq!(histogram.get_mean(), expected_mean, expected_mean * 0.01);
+}
+
+#[test]
+fn decayed_count_halves_every_half_life() {
+    let (mut histogram, start) = new_histogram();
+    for _ in 0..1024 {
+        succ!(histogram.record_value_at(100, start));
+    }
+    assert_approx_eq!(histogram.get_decayed_count_at(start), 1024.0, 0.5);
+    assert_approx_eq!(histogram.get_decayed_count_at(start + HALF_LIFE), 512.0, 0.5);
+    assert_approx_eq!(histogram.get_decayed_count_at(start + HALF_LIFE * 4), 64.0, 0.5);
+}
+
+#[test]
+fn rescaling_drops_fully_decayed_values() {
+    let (mut histogram, start) = new_histogram();
+    for _ in 0..1000 {
+        succ!(histogram.record_value_at(100, start));
+    }
+    let much_later = start + HALF_LIFE * 40;
+    succ!(histogram.record_value_at(5000, much_later));
+
+    assert_approx_eq!(histogram.get_decayed_count_at(much_later), 1.0, 0.01);
+    assert!(histogram.histogram().values_are_equivalent(5000, histogram.get_min_value()));
+    assert!(histogram.histogram().values_are_equivalent(5000, histogram.get_value_at_percentile(1.0)));
+}
+
+#[test]
+fn zero_half_life_is_rejected() {
+    assert_eq!(
+        Some(DecayingCreationError::HalfLifeIsZero),
+        DecayingHistogram::new(3600 * 1000 * 1000, 3, Duration::ZERO).err()
+    );
+}
+
+#[test]
+fn recording_long_after_the_landmark_rescales_once() {
+    let (mut histogram, start) = new_histogram();
+    succ!(histogram.record_value_at(100, start));
+    let far_later = start + HALF_LIFE * 1_000_000;
+    succ!(histogram.record_value_at(5000, far_later));
+    let now = far_later + HALF_LIFE * 20;
+    succ!(histogram.record_value_at(5000, now));
+
+    assert_approx_eq!(histogram.get_decayed_count_at(now), 1.0, 0.01);
+    assert!(histogram.histogram().values_are_equivalent(5000, histogram.get_min_value()));
+}
+
+#[test]
+fn overflowing_the_total_count_is_rejected() {
+    let (mut histogram, start) = new_histogram();
+    // each value recorded at the landmark weighs 2^16
+    assert_eq!(
+        Err(RecordError::CountOverflow),
+        histogram.record_value_with_count_at(100, 1 << 48, start)
+    );
+    assert_eq!(0, histogram.histogram().get_total_count());
+
+    succ!(histogram.record_value_with_count_at(100, (1 << 48) - 1, start));
+    let almost_rescaled = start + HALF_LIFE * 7;
+    assert_eq!(Err(RecordError::CountOverflow), histogram.record_value_at(100, almost_rescaled));
+    assert_eq!(u64::MAX - ((1 << 16) - 1), histogram.histogram().get_total_count());
+}
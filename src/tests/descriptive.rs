@@ -0,0 +1,44 @@
+use crate::st::Histogram;
+use crate::stats::descriptive::*;
+use crate::tests::util::*;
+
+#[test]
+fn empty_histogram_stats() {
+    let histogram = Histogram::<u64>::new(3).unwrap();
+    assert_eq!(0.0, trimmed_mean(&histogram, 5.0, 95.0));
+    assert_eq!(0.0, winsorized_mean(&histogram, 5.0, 95.0));
+    assert_eq!(0.0, median_absolute_deviation(&histogram));
+    assert_eq!(0.0, skewness(&histogram));
+    assert_eq!(0.0, kurtosis(&histogram));
+    assert_eq!(0.0, coefficient_of_variation(&histogram));
+    assert_eq!(None, mode(&histogram));
+}
+
+#[test]
+fn trimmed_and_winsorized_means_drop_outliers() {
+    let histogram = raw_stat_histo();
+    assert_approx_eq!(histogram.get_mean(), trimmed_mean(&histogram, 0.0, 100.0), 0.001);
+    assert_approx_eq!(1000.0, trimmed_mean(&histogram, 0.0, 99.0), 1.0);
+    assert_approx_eq!(1000.0, winsorized_mean(&histogram, 1.0, 99.0), 1.0);
+    assert!(histogram.get_mean() > 10000.0);
+}
+
+#[test]
+fn uniform_distribution_moments() {
+    let histogram = uniform_histogram(1, 1000, 3);
+    assert_approx_eq!(500.5, trimmed_mean(&histogram, 25.0, 75.0), 1.0);
+    assert_approx_eq!(250.0, median_absolute_deviation(&histogram), 2.0);
+    assert_approx_eq!(0.0, skewness(&histogram), 0.01);
+    assert_approx_eq!(1.8, kurtosis(&histogram), 0.01);
+    let expected_cv = (999.0 * 1001.0 / 12.0_f64).sqrt() / 500.5;
+    assert_approx_eq!(expected_cv, coefficient_of_variation(&histogram), 0.001);
+}
+
+#[test]
+fn skewed_distribution_moments() {
+    let histogram = raw_stat_histo();
+    assert!(skewness(&histogram) > 10.0);
+    assert!(kurtosis(&histogram) > 100.0);
+    assert_eq!(0.0, median_absolute_deviation(&histogram));
+    assert!(histogram.values_are_equivalent(1000, mode(&histogram).unwrap()));
+}
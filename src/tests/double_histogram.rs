@@ -26,6 +26,7 @@ trait TestDoubleHistogram: Sized {
     fn get_mean(&self) -> f64;
     fn get_std_deviation(&self) -> f64;
     fn get_value_at_percentile(&self, percentile: f64) -> f64;
+    fn get_values_at_percentiles(&self, percentiles: &[f64]) -> Vec<f64>;
     fn size_of_equivalent_value_range(&self, value: f64) -> f64;
     fn lowest_equivalent_value(&self, value: f64) -> f64;
     fn highest_equivalent_value(&self, value: f64) -> f64;
@@ -93,6 +94,9 @@ impl TestDoubleHistogram for DoubleHistogram {
     fn get_value_at_percentile(&self, percentile: f64) -> f64 {
         DoubleHistogram::get_value_at_percentile(self, percentile)
     }
+    fn get_values_at_percentiles(&self, percentiles: &[f64]) -> Vec<f64> {
+        DoubleHistogram::get_values_at_percentiles(self, percentiles)
+    }
     fn size_of_equivalent_value_range(&self, value: f64) -> f64 {
         DoubleHistogram::size_of_equivalent_value_range(self, value)
     }
@@ -194,6 +198,9 @@ impl TestDoubleHistogram for ConcurrentDoubleHistogram {
     fn get_value_at_percentile(&self, percentile: f64) -> f64 {
         ConcurrentDoubleHistogram::get_value_at_percentile(self, percentile)
     }
+    fn get_values_at_percentiles(&self, percentiles: &[f64]) -> Vec<f64> {
+        ConcurrentDoubleHistogram::get_values_at_percentiles(self, percentiles)
+    }
     fn size_of_equivalent_value_range(&self, value: f64) -> f64 {
         ConcurrentDoubleHistogram::size_of_equivalent_value_range(self, value)
     }
@@ -768,6 +775,22 @@ fn get_value_at_percentile() {
     run_get_value_at_percentile_test::<ConcurrentDoubleHistogram>();
 }
 
+fn run_get_values_at_percentiles_test<H: TestDoubleHistogram>() {
+    let data = build_data_histograms::<H>();
+    let percentiles = [99.999, 30.0, 100.0, 75.0, 0.0, 90.0];
+    let values = data.histogram.get_values_at_percentiles(&percentiles);
+    assert_eq!(percentiles.len(), values.len());
+    for (percentile, value) in percentiles.iter().zip(values) {
+        assert_eq!(data.histogram.get_value_at_percentile(*percentile), value);
+    }
+}
+
+#[test]
+fn get_values_at_percentiles() {
+    run_get_values_at_percentiles_test::<DoubleHistogram>();
+    run_get_values_at_percentiles_test::<ConcurrentDoubleHistogram>();
+}
+
 fn run_get_value_at_percentile_examples_test<H: TestDoubleHistogram>() {
     let mut histogram =
         H::with_highest_to_lowest_value_ratio(TRACKABLE_VALUE_RANGE_SIZE, NUMBER_OF_SIGNIFICANT_VALUE_DIGITS).unwrap();
@@ -0,0 +1,76 @@
+use crate::concurrent::{DoubleRecorder, SaturatingDoubleRecorder, SingleWriterDoubleRecorder};
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn samples_are_double_histograms() {
+    let recorder = DoubleRecorder::new(3).unwrap();
+    succ!(recorder.record_value(0.000_25));
+    succ!(recorder.record_value_with_count(0.5, 3));
+
+    let sample = recorder.locking_sample();
+    let histogram = sample.histogram();
+    assert_eq!(4, histogram.get_total_count());
+    assert!(histogram.values_are_equivalent(0.000_25, histogram.get_min_value()));
+    assert!(histogram.values_are_equivalent(0.5, histogram.get_value_at_percentile(50.0)));
+    assert!(histogram.meta_data().start_timestamp.is_some());
+    assert!(histogram.meta_data().end_timestamp.is_some());
+
+    let sample = sample.resample();
+    let histogram = sample.histogram();
+    assert_eq!(0, histogram.get_total_count());
+    assert!(histogram.meta_data().start_timestamp.is_some());
+    assert!(histogram.meta_data().end_timestamp.is_some());
+    assert!(histogram.is_auto_resize());
+}
+
+#[test]
+fn expected_interval_backfills() {
+    let recorder = SaturatingDoubleRecorder::with_highest_to_lowest_value_ratio(1 << 20, 2).unwrap();
+    succ!(recorder.record_value_with_expected_interval(1.0, 0.25));
+    assert_eq!(4, recorder.locking_sample().histogram().get_total_count());
+}
+
+#[test]
+fn no_values_lost_across_samples() {
+    let recorder = Arc::new(DoubleRecorder::new(2).unwrap());
+    let writers: Vec<_> = (0..4)
+        .map(|_| {
+            let recorder = recorder.clone();
+            thread::spawn(move || {
+                for i in 0..20_000 {
+                    succ!(recorder.record_value(1.0 + (i % 100) as f64 / 1000.0));
+                }
+            })
+        })
+        .collect();
+
+    let mut total = 0;
+    let mut sample = recorder.locking_sample();
+    while !writers.iter().all(|w| w.is_finished()) {
+        total += sample.histogram().get_total_count();
+        sample = sample.resample();
+    }
+    total += sample.histogram().get_total_count();
+    drop(sample);
+    for writer in writers {
+        writer.join().unwrap();
+    }
+    total += recorder.locking_sample().histogram().get_total_count();
+    assert_eq!(80_000, total);
+}
+
+#[test]
+fn single_writer_double_recorder() {
+    let mut recorder = SingleWriterDoubleRecorder::new(3).unwrap();
+    let sampler = recorder.sampler();
+    succ!(recorder.record_value(0.001));
+    succ!(recorder.record_value(2.5));
+
+    let sample = sampler.locking_sample();
+    assert_eq!(2, sample.get_total_count());
+    assert!(sample.values_are_equivalent(2.5, sample.get_max_value()));
+    assert!(sample.meta_data().end_timestamp.is_some());
+    let sample = sample.resample();
+    assert_eq!(0, sample.get_total_count());
+}
@@ -0,0 +1,94 @@
+use crate::concurrent::recorder;
+use crate::concurrent::DurationRecorder;
+use crate::core::*;
+use crate::st::DurationHistogram;
+use std::time::Duration;
+
+fn new_histogram(time_unit: TimeUnit) -> DurationHistogram<u64> {
+    DurationHistogram::<u64>::with_low_high_sigvdig(time_unit, Duration::from_nanos(1), Duration::from_secs(3600), 3).unwrap()
+}
+
+#[test]
+fn time_unit_conversions() {
+    assert_eq!(1500, TimeUnit::Microseconds.to_value(Duration::from_micros(1500)));
+    assert_eq!(1, TimeUnit::Milliseconds.to_value(Duration::from_micros(1999)));
+    assert_eq!(u64::MAX, TimeUnit::Nanoseconds.to_value(Duration::from_secs(u64::MAX)));
+    assert_eq!(Duration::from_millis(2500), TimeUnit::Milliseconds.to_duration(2500));
+    assert_eq!(Duration::from_nanos(1500), TimeUnit::Microseconds.to_duration_f64(1.5));
+    assert_eq!("us", TimeUnit::Microseconds.symbol());
+}
+
+#[test]
+fn queries_return_durations() {
+    let mut histogram = new_histogram(TimeUnit::Microseconds);
+    for ms in 1..=100 {
+        succ!(histogram.record(Duration::from_millis(ms)));
+    }
+
+    assert_eq!(Some(TimeUnit::Microseconds), histogram.histogram().meta_data.time_unit);
+    assert_eq!(100, histogram.get_total_count());
+    assert_eq!(Duration::from_millis(1), histogram.min());
+    assert_eq!(Duration::from_micros(100_031), histogram.max());
+    assert_eq!(histogram.histogram().get_value_at_percentile(99.0), histogram.value_at_percentile(99.0).as_micros() as u64);
+    assert!((histogram.mean().as_secs_f64() - 0.0505).abs() < 0.0001);
+    assert_eq!(vec![histogram.value_at_percentile(50.0), histogram.value_at_percentile(90.0)], histogram.values_at_percentiles(&[50.0, 90.0]));
+    assert_eq!(1.0, histogram.percentile_at_or_below(Duration::from_micros(1000)));
+}
+
+#[test]
+fn iteration_yields_durations() {
+    let mut histogram = new_histogram(TimeUnit::Milliseconds);
+    succ!(histogram.record(Duration::from_millis(5)));
+    succ!(histogram.record(Duration::from_millis(7)));
+    succ!(histogram.record(Duration::from_millis(7)));
+
+    let values: Vec<_> = histogram
+        .recorded_values()
+        .map(|v| (v.duration_iterated_to, v.count_at_value_iterated_to))
+        .collect();
+    assert_eq!(vec![(Duration::from_millis(5), 1), (Duration::from_millis(7), 2)], values);
+}
+
+#[test]
+fn adding_a_histogram_in_another_unit_is_rejected() {
+    let mut nanos = new_histogram(TimeUnit::Nanoseconds);
+    let mut micros = new_histogram(TimeUnit::Microseconds);
+    succ!(micros.record(Duration::from_micros(5)));
+    assert_eq!(Err(RecordError::TimeUnitMismatch), nanos.add(&micros));
+    assert_eq!(0, nanos.get_total_count());
+
+    let mut more_micros = new_histogram(TimeUnit::Microseconds);
+    succ!(more_micros.add(&micros));
+    assert_eq!(Duration::from_micros(5), more_micros.max());
+}
+
+#[test]
+fn reset_keeps_time_unit() {
+    let mut histogram = new_histogram(TimeUnit::Nanoseconds);
+    succ!(histogram.record(Duration::from_nanos(10)));
+    histogram.reset();
+    assert_eq!(0, histogram.get_total_count());
+    assert_eq!(Some(TimeUnit::Nanoseconds), histogram.histogram().meta_data.time_unit);
+}
+
+#[test]
+fn recorder_samples_carry_time_unit() {
+    let recorder = DurationRecorder::from_recorder(
+        recorder::resizable_with_low_high_sigvdig(1, 3600 * 1000, 3).unwrap(),
+        TimeUnit::Milliseconds,
+    );
+    succ!(recorder.record(Duration::from_millis(10)));
+    succ!(recorder.record(Duration::from_millis(30)));
+
+    let sample = recorder.locking_sample();
+    assert_eq!(2, sample.get_total_count());
+    assert_eq!(Duration::from_millis(10), sample.min());
+    assert_eq!(Duration::from_millis(30), sample.max());
+    assert_eq!(Duration::from_millis(20), sample.mean());
+    assert_eq!(Duration::from_millis(30), sample.value_at_percentile(99.0));
+    assert_eq!(Some(TimeUnit::Milliseconds), sample.sample().histogram().meta_data().time_unit);
+
+    let sample = sample.resample();
+    assert_eq!(0, sample.get_total_count());
+    assert_eq!(Some(TimeUnit::Milliseconds), sample.sample().histogram().meta_data().time_unit);
+}
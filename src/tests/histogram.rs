@@ -628,6 +628,50 @@ fn value_at_percentile() {
     );
 }
 
+#[test]
+fn values_at_percentiles_match_single_queries() {
+    let data = build_data_histograms();
+    let percentiles = [50.0, 0.0, 99.999, 100.0, 30.0, 99.0, 75.0, 99.0, 150.0];
+    for histogram in [&data.histogram, &data.scaled_histogram, &data.raw_histogram].iter() {
+        let values = histogram.get_values_at_percentiles(&percentiles);
+        assert_eq!(percentiles.len(), values.len());
+        for (percentile, value) in percentiles.iter().zip(values) {
+            assert_eq!(histogram.get_value_at_percentile(*percentile), value);
+        }
+    }
+    let empty = Histogram::<u64>::new(SIG_V_DIGITS).unwrap();
+    assert_eq!(vec![0, 0], empty.get_values_at_percentiles(&[50.0, 100.0]));
+}
+
+#[test]
+fn indexed_queries_match_linear_scans() {
+    let data = build_data_histograms();
+    let percentiles = [0.0, 30.0, 50.0, 75.0, 90.0, 99.0, 99.999, 100.0];
+    for histogram in [&data.histogram, &data.scaled_histogram, &data.raw_histogram].iter() {
+        let indexed = histogram.indexed();
+        for percentile in percentiles.iter() {
+            assert_eq!(histogram.get_value_at_percentile(*percentile), indexed.get_value_at_percentile(*percentile));
+        }
+        assert_eq!(histogram.get_values_at_percentiles(&percentiles), indexed.get_values_at_percentiles(&percentiles));
+        for value in [0, 1000, 5000, 100000000, u64::MAX].iter() {
+            assert_approx_eq!(
+                histogram.get_percentile_at_or_below_value(*value),
+                indexed.get_percentile_at_or_below_value(*value),
+                0.0000001
+            );
+        }
+    }
+
+    let indexed = data.raw_histogram.indexed();
+    assert_eq!(10000, indexed.get_count_between_values(1000, 1000));
+    assert_eq!(10001, indexed.get_count_between_values(0, u64::MAX));
+    assert_eq!(0, indexed.get_count_between_values(2000, 1000));
+
+    let empty = Histogram::<u64>::new(SIG_V_DIGITS).unwrap();
+    assert_eq!(0, empty.indexed().get_value_at_percentile(50.0));
+    assert_approx_eq!(100.0, empty.indexed().get_percentile_at_or_below_value(0), 0.0000001);
+}
+
 #[test]
 fn get_value_at_percentile_for_large_histogram() {
     let largest_value = 1000000000000;
@@ -935,3 +979,31 @@ fn histogram_shift_non_lowest_bucket() {
         assert!(histogram.equals(&original));
     }
 }
+
+#[test]
+fn with_precision_rebuckets_counts() {
+    let histogram = raw_stat_histo();
+    let (reduced, dropped) = histogram.with_precision(2).unwrap();
+    assert_eq!(0, dropped);
+    assert_eq!(2, reduced.get_number_of_significant_value_digits());
+    assert_eq!(histogram.get_total_count(), reduced.get_total_count());
+    assert_eq!(Some(10000), reduced.get_count_at_value(1000));
+    assert!(reduced.values_are_equivalent(reduced.get_max_value(), 100000000));
+    assert!(reduced.counts_array_length() < histogram.counts_array_length());
+}
+
+#[test]
+fn with_range_reports_dropped_values() {
+    let histogram = raw_stat_histo();
+    let (narrowed, dropped) = histogram.with_range(1, 1000 * 1000).unwrap();
+    assert_eq!(1, dropped);
+    assert_eq!(10000, narrowed.get_total_count());
+    assert_eq!(Some(10000), narrowed.get_count_at_value(1000));
+    assert_eq!(narrowed.highest_equivalent_value(1000), narrowed.get_max_value());
+}
+
+#[test]
+fn with_range_rejects_invalid_range() {
+    let histogram = raw_stat_histo();
+    assert!(histogram.with_range(0, 1000).is_err());
+}
@@ -1,4 +1,5 @@
 use crate::core::HistogramSettings;
+use crate::core::histogram_settings::counts_array_length_for;
 use crate::core::errors::*;
 
 #[test]
@@ -135,3 +136,26 @@ fn unit_magnitude_62_sub_bucket_magnitude_1_ok() {
     assert_eq!(1, s.get_bucket_index(u64::MAX));
     assert_eq!(1, s.get_sub_bucket_index(u64::MAX, 1));
 }
+
+#[test]
+fn const_counts_array_length_matches_settings() {
+    let params = [
+        (1, 2, 0),
+        (1, 3600 * 1000 * 1000, 2),
+        (1, 3600 * 1000 * 1000, 3),
+        (1_u64 << 12, 1_u64 << 32, 3),
+        (1000, u64::MAX, 5),
+        ((1_u64 << 62) - 1, u64::MAX, 0),
+        (1_u64 << 55, 1 << 63, 2),
+    ];
+    for &(low, high, digits) in params.iter() {
+        let s = HistogramSettings::new(low, high, digits).unwrap();
+        assert_eq!(s.counts_array_length, counts_array_length_for(low, high, digits));
+    }
+}
+
+#[test]
+#[should_panic]
+fn const_counts_array_length_rejects_invalid_settings() {
+    counts_array_length_for(1_u64 << 53, 1 << 63, 3);
+}
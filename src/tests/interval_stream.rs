@@ -0,0 +1,113 @@
+use crate::concurrent::recorder;
+use crate::concurrent::IntervalStream;
+use futures_core::Stream;
+use std::future::{self, Ready};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+const HIGHEST_TRACKABLE: u64 = 3600 * 1000 * 1000;
+const PERIOD: Duration = Duration::from_millis(10);
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+// polls the stream on the current thread, parking it while the stream is pending
+fn next<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match Pin::new(&mut *stream).poll_next(&mut cx) {
+            Poll::Ready(item) => return item,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+fn sleeping_timer(sleeps: &AtomicUsize) -> impl FnMut(Instant) -> Ready<()> + '_ {
+    move |deadline| {
+        sleeps.fetch_add(1, Ordering::Relaxed);
+        thread::sleep(deadline.saturating_duration_since(Instant::now()));
+        future::ready(())
+    }
+}
+
+fn assert_send<S: Send>(_: &S) {}
+
+#[test]
+fn stream_yields_each_interval() {
+    let recorder = recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 2).unwrap();
+    let sleeps = AtomicUsize::new(0);
+    let started = Instant::now();
+    let mut stream = recorder.interval_stream(PERIOD, sleeping_timer(&sleeps));
+    assert_send(&stream);
+
+    for value in 1..=3 {
+        succ!(recorder.record_value(value));
+    }
+    let first = next(&mut stream).unwrap();
+    assert!(started.elapsed() >= PERIOD);
+    assert_eq!(3, first.get_total_count());
+    assert_eq!(3, first.get_max_value());
+
+    succ!(recorder.record_value(100));
+    let second = next(&mut stream).unwrap();
+    assert!(started.elapsed() >= 2 * PERIOD);
+    assert_eq!(1, second.get_total_count());
+    assert_eq!(100, second.get_min_value());
+    assert_eq!(2, sleeps.load(Ordering::Relaxed));
+
+    // consecutive intervals don't overlap
+    let first_end = first.meta_data.end_timestamp.unwrap();
+    let second_start = second.meta_data.start_timestamp.unwrap();
+    assert!(first.meta_data.start_timestamp.unwrap() <= first_end);
+    assert!(first_end >= second_start);
+    assert!(second_start <= second.meta_data.end_timestamp.unwrap());
+}
+
+#[test]
+fn stream_backs_off_while_another_reader_holds_the_lock() {
+    let recorder = recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 2).unwrap();
+    let sleeps = AtomicUsize::new(0);
+    let locked = Barrier::new(2);
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            let sample = recorder.locking_sample();
+            locked.wait();
+            thread::sleep(5 * PERIOD);
+            drop(sample);
+        });
+        locked.wait();
+        succ!(recorder.record_value(7));
+
+        let mut stream = IntervalStream::new(&recorder, PERIOD, sleeping_timer(&sleeps));
+        let interval = next(&mut stream).unwrap();
+        assert_eq!(1, interval.get_total_count());
+    });
+    assert!(sleeps.load(Ordering::Relaxed) > 1);
+}
+
+#[test]
+fn recorder_keeps_sampling_after_the_stream_is_dropped() {
+    let recorder = recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 2).unwrap();
+    let sleeps = AtomicUsize::new(0);
+    succ!(recorder.record_value(1));
+    {
+        let mut stream = recorder.interval_stream(PERIOD, sleeping_timer(&sleeps));
+        assert_eq!(1, next(&mut stream).unwrap().get_total_count());
+    }
+
+    succ!(recorder.record_value(2));
+    let interval = recorder.interval_histogram();
+    assert_eq!(1, interval.get_total_count());
+    assert_eq!(2, interval.get_max_value());
+}
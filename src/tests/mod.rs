@@ -9,3 +9,19 @@ mod iteration;
 mod bench;
 mod double_histogram;
 mod encoding;
+mod comparison;
+mod descriptive;
+mod confidence;
+mod decaying_histogram;
+mod sliding_window_histogram;
+mod signed_histogram;
+mod duration;
+mod single_writer_recorder;
+mod double_recorder;
+mod sharded_recorder;
+mod recorder_registry;
+mod reporter;
+#[cfg(feature = "async")]
+mod interval_stream;
+mod writer_reader_phaser;
+mod phased_double_buffer;
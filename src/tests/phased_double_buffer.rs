@@ -0,0 +1,58 @@
+use crate::concurrent::PhasedDoubleBuffer;
+use std::mem;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+const WRITERS: u64 = 4;
+const INCREMENTS_PER_WRITER: u64 = 100_000;
+
+#[test]
+fn swapped_counts_add_up_to_every_write() {
+    let counter = PhasedDoubleBuffer::<AtomicU64>::default();
+    let mut swapped = 0;
+    thread::scope(|scope| {
+        let writers: Vec<_> = (0..WRITERS)
+            .map(|_| {
+                scope.spawn(|| {
+                    for _ in 0..INCREMENTS_PER_WRITER {
+                        counter.write(|count| count.fetch_add(1, Ordering::Relaxed));
+                    }
+                })
+            })
+            .collect();
+        while writers.iter().any(|writer| !writer.is_finished()) {
+            swapped += counter.swap(|count| mem::take(count.get_mut()));
+            thread::yield_now();
+        }
+    });
+    swapped += counter.swap(|count| mem::take(count.get_mut()));
+    assert_eq!(WRITERS * INCREMENTS_PER_WRITER, swapped);
+    assert_eq!(0, counter.swap(|count| *count.get_mut()));
+}
+
+#[test]
+fn reader_prepares_the_inactive_value_before_swapping() {
+    let events = PhasedDoubleBuffer::new(Mutex::new(vec!["first"]), Mutex::new(Vec::new()));
+    let writing = AtomicBool::new(true);
+    thread::scope(|scope| {
+        let guard = events.begin_write();
+        guard.lock().unwrap().push("second");
+
+        scope.spawn(|| {
+            let mut reader = events.reader();
+            reader.inactive().get_mut().unwrap().push("swapped in");
+            let swapped_out = reader.swap().get_mut().unwrap();
+            // the swap waits for the writer that held the guard
+            assert!(!writing.load(Ordering::SeqCst));
+            assert_eq!(vec!["first", "second"], *swapped_out);
+        });
+
+        thread::sleep(Duration::from_millis(10));
+        writing.store(false, Ordering::SeqCst);
+        drop(guard);
+    });
+    events.write(|events| events.lock().unwrap().push("third"));
+    assert_eq!(vec!["swapped in", "third"], events.swap(|events| mem::take(events.get_mut().unwrap())));
+}
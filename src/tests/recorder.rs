@@ -1,12 +1,15 @@
+use crate::concurrent::latency_timer::Clock;
 use crate::concurrent::recordable_histogram::RecordableHistogram;
-use crate::concurrent::resizable_histogram::ResizableHistogram;
+use crate::concurrent::resizable_histogram::{ResizableHistogram, ResizableHistogram16, ResizableHistogram32};
 use crate::concurrent::recorder::{self, Recorder};
+use crate::concurrent::DurationRecorder;
 use crate::core::constants::ORIGINAL_MIN;
 use crate::core::*;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::thread;
-use std::time::Duration;
+use std::cell::Cell;
+use std::time::{Duration, Instant};
 use rand::Rng;
 
 const HIGHEST_TRACKABLE: u64 = 3600 * 1000 * 1000;
@@ -18,6 +21,12 @@ fn resizing_recorder() {
     run_recorder_test(recorder);
 }
 
+#[test]
+fn resizing_recorder_with_32_bit_counters() {
+    let histogram = ResizableHistogram32::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 2).unwrap();
+    run_recorder_test(Arc::new(Recorder::from_histogram(histogram)));
+}
+
 #[test]
 fn static_recorder() {
     let recorder = Arc::new(recorder::static_with_low_high_sigvdig::<STATIC_COUNTS_LEN>(1, HIGHEST_TRACKABLE, 2).unwrap());
@@ -52,6 +61,157 @@ fn recorder_resample_resets_tracking() {
     }
 }
 
+#[test]
+fn snapshot_values_at_percentiles() {
+    let recorder = recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 2).unwrap();
+    for value in 1..=1000 {
+        succ!(recorder.record_value(value));
+    }
+
+    let sample = recorder.locking_sample();
+    let snapshot = sample.histogram();
+    let percentiles = [99.0, 50.0, 100.0];
+    let values = snapshot.get_values_at_percentiles(&percentiles);
+    for (percentile, value) in percentiles.iter().zip(values) {
+        assert_eq!(snapshot.get_value_at_percentile(*percentile), value);
+    }
+    assert!(snapshot.settings().values_are_equivalent(500, snapshot.get_value_at_percentile(50.0)));
+}
+
+#[test]
+fn sample_into_recycles_target() {
+    let recorder = recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 2).unwrap();
+    let mut interval = Box::new(ResizableHistogram::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 2).unwrap());
+    for round in 1..=5 {
+        for value in 0..round {
+            succ!(recorder.record_value(100 * (value + 1)));
+        }
+        recorder.sample_into(&mut interval);
+        assert_eq!(round, interval.get_total_count());
+        assert_eq!(Some(1), interval.get_count_at_index(interval.settings().counts_array_index(100)));
+        assert!(interval.meta_data().end_timestamp.is_some());
+    }
+
+    // an interval histogram of another configuration is not recycled into the recorder
+    let mut other = Box::new(ResizableHistogram::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap());
+    succ!(other.record_value(1));
+    succ!(recorder.record_value(7));
+    recorder.sample_into(&mut other);
+    assert_eq!(1, other.get_total_count());
+    assert_eq!(2, other.settings().number_of_significant_value_digits);
+    succ!(recorder.record_value(9));
+    assert_eq!(1, recorder.locking_sample().histogram().get_total_count());
+}
+
+struct ManualClock(Cell<Instant>);
+
+impl ManualClock {
+    fn advance(&self, by: Duration) {
+        self.0.set(self.0.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.0.get()
+    }
+}
+
+#[test]
+fn latency_timer_records_on_drop() {
+    let recorder = recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap();
+    let clock = ManualClock(Cell::new(Instant::now()));
+    {
+        let _timer = recorder.start_timer_with_clock(&clock);
+        clock.advance(Duration::from_micros(1500));
+    }
+    let timer = recorder.start_timer_with_clock(&clock);
+    clock.advance(Duration::from_micros(20));
+    let (elapsed, result) = timer.stop();
+    succ!(result);
+    assert_eq!(Duration::from_micros(20), elapsed);
+    recorder.start_timer_with_clock(&clock).cancel();
+
+    let sample = recorder.locking_sample();
+    let snapshot = sample.histogram();
+    assert_eq!(2, snapshot.get_total_count());
+    assert!(snapshot.settings().values_are_equivalent(20_000, snapshot.get_value_at_percentile(0.0)));
+    assert!(snapshot.settings().values_are_equivalent(1_500_000, snapshot.get_max_value()));
+}
+
+#[test]
+fn latency_timer_corrects_for_expected_interval() {
+    let recorder = recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap();
+    let clock = ManualClock(Cell::new(Instant::now()));
+    let timer = recorder
+        .start_timer_with_clock(&clock)
+        .with_expected_interval(Duration::from_micros(100));
+    clock.advance(Duration::from_micros(1000));
+    drop(timer);
+    let start = clock.now();
+    clock.advance(Duration::from_micros(300));
+    succ!(recorder.record_since_with_clock(&clock, start, Duration::from_micros(100)));
+
+    let sample = recorder.locking_sample();
+    assert_eq!(10 + 3, sample.histogram().get_total_count());
+}
+
+#[test]
+fn record_since_saturates_elapsed_nanos() {
+    let recorder = recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap();
+    let clock = ManualClock(Cell::new(Instant::now()));
+    let start = clock.now();
+    clock.advance(Duration::from_millis(5));
+    succ!(recorder.record_since_with_clock(&clock, start, Duration::ZERO));
+    // a start in the future records zero rather than wrapping
+    succ!(recorder.record_since_with_clock(&clock, start + Duration::from_secs(1), Duration::ZERO));
+    succ!(recorder.record_since(Instant::now()));
+
+    let sample = recorder.locking_sample();
+    let snapshot = sample.histogram();
+    assert_eq!(3, snapshot.get_total_count());
+    assert!(snapshot.settings().values_are_equivalent(5_000_000, snapshot.get_max_value()));
+}
+
+#[test]
+fn timers_record_in_the_recorders_time_unit() {
+    let recorder = recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap();
+    assert_eq!(None, recorder.time_unit());
+    recorder.set_time_unit(TimeUnit::Microseconds);
+    assert_eq!(Some(TimeUnit::Microseconds), recorder.time_unit());
+
+    let clock = ManualClock(Cell::new(Instant::now()));
+    let timer = recorder.start_timer_with_clock(&clock);
+    clock.advance(Duration::from_millis(2));
+    drop(timer);
+    let start = clock.now();
+    clock.advance(Duration::from_millis(3));
+    succ!(recorder.record_since_with_clock(&clock, start, Duration::ZERO));
+
+    let sample = recorder.locking_sample();
+    let snapshot = sample.histogram();
+    assert_eq!(2, snapshot.get_total_count());
+    assert!(snapshot.settings().values_are_equivalent(2_000, snapshot.get_min_non_zero_value()));
+    assert!(snapshot.settings().values_are_equivalent(3_000, snapshot.get_max_value()));
+}
+
+#[test]
+fn duration_recorder_records_since_with_clock() {
+    let recorder = DurationRecorder::from_recorder(
+        recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap(),
+        TimeUnit::Milliseconds,
+    );
+    let clock = ManualClock(Cell::new(Instant::now()));
+    let start = clock.now();
+    clock.advance(Duration::from_millis(40));
+    succ!(recorder.record_since_with_clock(&clock, start, Duration::from_millis(10)));
+
+    let sample = recorder.locking_sample();
+    assert_eq!(4, sample.get_total_count());
+    assert_eq!(Duration::from_millis(40), sample.max());
+    assert_eq!(Duration::from_millis(10), sample.min());
+}
+
 #[test]
 fn clear_counts_resets_metadata() {
     let mut histogram = ResizableHistogram::with_low_high_sigvdig(
@@ -174,3 +334,78 @@ fn run_recorder_test<T: 'static + RecordableHistogram>(recorder: Arc<Recorder<T>
 
     assert_approx_eq!(total, observed_value, total as f64 * 0.005);
 }
+
+#[test]
+fn interval_histogram_is_owned() {
+    let recorder = Arc::new(recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 2).unwrap());
+    recorder.set_time_unit(TimeUnit::Microseconds);
+    for value in 1..=100 {
+        succ!(recorder.record_value(value * 10));
+    }
+
+    let interval = recorder.interval_histogram();
+    let reporter = thread::spawn(move || {
+        assert_eq!(100, interval.get_total_count());
+        assert_eq!(10, interval.get_min_value());
+        assert!(interval.values_are_equivalent(1000, interval.get_max_value()));
+        assert!(interval.meta_data.start_timestamp.is_some());
+        assert!(interval.meta_data.end_timestamp.is_some());
+        assert_eq!(Some(TimeUnit::Microseconds), interval.meta_data.time_unit);
+    });
+    succ!(recorder.record_value(5));
+    reporter.join().unwrap();
+
+    let interval = recorder.interval_histogram();
+    assert_eq!(1, interval.get_total_count());
+    assert!(interval.meta_data.start_timestamp.is_some());
+    assert_eq!(0, recorder.interval_histogram().get_total_count());
+}
+
+#[test]
+fn batch_records_into_the_active_histogram() {
+    let recorder = recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 2).unwrap();
+    recorder.batch(|batch| {
+        succ!(batch.record_value(10));
+        succ!(batch.record_value_with_count(20, 3));
+        succ!(batch.record_value_with_expected_interval(100, 25));
+        succ!(batch.record_values(&[1, 2, 3]));
+    });
+
+    let interval = recorder.interval_histogram();
+    // 100 with an expected interval of 25 adds 75, 50 and 25
+    assert_eq!(11, interval.get_total_count());
+    assert_eq!(Some(3), interval.get_count_at_value(20));
+    assert_eq!(Some(1), interval.get_count_at_value(75));
+    assert_eq!(1, interval.get_min_value());
+}
+
+#[test]
+fn record_values_spans_chunks_and_stops_at_the_first_failure() {
+    let recorder = recorder::static_with_low_high_sigvdig::<STATIC_COUNTS_LEN>(1, HIGHEST_TRACKABLE, 2).unwrap();
+    let values: Vec<u64> = (1..=3 * recorder::RECORD_VALUES_CHUNK_LENGTH as u64 + 1).collect();
+    succ!(recorder.record_values(&values));
+    assert_eq!(values.len() as u64, recorder.interval_histogram().get_total_count());
+
+    let histogram = ResizableHistogram16::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 2).unwrap();
+    let recorder = Recorder::from_histogram(histogram);
+    succ!(recorder.record_value_with_count(5, u16::MAX as u64));
+    assert_eq!(Err(RecordError::CountOverflow), recorder.record_values(&[1, 2, 5, 3]));
+    assert_eq!(u16::MAX as u64 + 2, recorder.interval_histogram().get_total_count());
+}
+
+#[test]
+fn sample_waits_for_an_open_batch() {
+    let recorder = recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 2).unwrap();
+    let batch = recorder.begin_batch();
+    succ!(batch.record_value(1));
+
+    thread::scope(|scope| {
+        let sampler = scope.spawn(|| recorder.interval_histogram());
+        thread::sleep(Duration::from_millis(10));
+        assert!(!sampler.is_finished());
+        succ!(batch.record_value(2));
+        drop(batch);
+        assert_eq!(2, sampler.join().unwrap().get_total_count());
+    });
+    assert_eq!(0, recorder.interval_histogram().get_total_count());
+}
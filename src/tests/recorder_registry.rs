@@ -0,0 +1,57 @@
+use crate::concurrent::RecorderRegistry;
+use crate::core::TagError;
+use std::sync::Arc;
+
+const HIGHEST_TRACKABLE: u64 = 3600 * 1000 * 1000;
+
+#[test]
+fn recorders_are_created_once_per_name_and_label_set() {
+    let registry = RecorderRegistry::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap();
+    let a = registry.recorder("latency", &[("route", "/users"), ("method", "GET")]).unwrap();
+    let b = registry.recorder("latency", &[("method", "GET"), ("route", "/users")]).unwrap();
+    let c = registry.recorder("latency", &[("route", "/users"), ("method", "POST")]).unwrap();
+    assert!(Arc::ptr_eq(&a, &b));
+    assert!(!Arc::ptr_eq(&a, &c));
+    assert_eq!(2, registry.len());
+
+    assert!(registry.remove("latency", &[("method", "POST"), ("route", "/users")]).is_some());
+    assert_eq!(1, registry.len());
+}
+
+#[test]
+fn sample_tags_every_interval_histogram() {
+    let registry = RecorderRegistry::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap();
+    let users = registry.recorder("latency", &[("route", "/users")]).unwrap();
+    let orders = registry.recorder("latency", &[("route", "/orders")]).unwrap();
+    let queue = registry.recorder("queue_depth", &[]).unwrap();
+    succ!(users.record_value(100));
+    succ!(users.record_value(200));
+    succ!(orders.record_value(300));
+
+    let samples = registry.sample();
+    let tags: Vec<_> = samples
+        .iter()
+        .map(|(_, histogram)| histogram.meta_data.tag.clone().unwrap())
+        .collect();
+    assert_eq!(vec!["latency;route=/orders", "latency;route=/users", "queue_depth"], tags);
+    let counts: Vec<_> = samples.iter().map(|(_, histogram)| histogram.get_total_count()).collect();
+    assert_eq!(vec![1, 2, 0], counts);
+    assert_eq!("latency", samples[0].0.name());
+    assert!(samples[0].1.meta_data.end_timestamp.is_some());
+
+    succ!(queue.record_value(7));
+    let samples = registry.sample();
+    let counts: Vec<_> = samples.iter().map(|(_, histogram)| histogram.get_total_count()).collect();
+    assert_eq!(vec![0, 0, 1], counts);
+}
+
+#[test]
+fn rejects_names_and_labels_unfit_for_tags() {
+    let registry = RecorderRegistry::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap();
+    assert_eq!(Some(TagError::Empty), registry.recorder("", &[]).err());
+    assert_eq!(Some(TagError::InvalidCharacter(' ')), registry.recorder("request latency", &[]).err());
+    assert_eq!(Some(TagError::InvalidCharacter(',')), registry.recorder("latency", &[("route", "a,b")]).err());
+    assert_eq!(Some(TagError::InvalidCharacter('=')), registry.recorder("latency", &[("a=b", "c")]).err());
+    assert!(registry.is_empty());
+    assert!(RecorderRegistry::with_low_high_sigvdig(0, HIGHEST_TRACKABLE, 3).is_err());
+}
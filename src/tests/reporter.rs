@@ -0,0 +1,210 @@
+use crate::concurrent::recorder;
+use crate::concurrent::{HgrmSink, IntervalSink, Reporter};
+#[cfg(feature = "encoding-base64")]
+use crate::concurrent::HlogSink;
+use crate::core::TimeUnit;
+#[cfg(feature = "encoding-base64")]
+use crate::encoding;
+use crate::st::Histogram;
+use parking_lot::Mutex;
+use std::io;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+#[cfg(feature = "encoding-base64")]
+use std::time::SystemTime;
+
+const HIGHEST_TRACKABLE: u64 = 3600 * 1000 * 1000;
+
+type Collected = Arc<Mutex<Vec<Histogram<u64>>>>;
+
+fn collecting_sink() -> (Collected, Box<dyn IntervalSink>) {
+    let collected = Arc::new(Mutex::new(Vec::new()));
+    let sink = {
+        let collected = collected.clone();
+        move |histogram: &Histogram<u64>| {
+            collected.lock().push(Histogram::copy_of(histogram));
+            Ok(())
+        }
+    };
+    (collected, Box::new(sink))
+}
+
+#[test]
+fn reports_a_final_tagged_interval_on_drop() {
+    let users = Arc::new(recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap());
+    let orders = Arc::new(recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap());
+    let (collected, sink) = collecting_sink();
+    let reporter = Reporter::start(
+        Duration::from_secs(3600),
+        vec![(Some("users".to_owned()), users.clone()), (None, orders.clone())],
+        vec![sink],
+    ).unwrap();
+    succ!(users.record_value(10));
+    succ!(users.record_value(20));
+    succ!(orders.record_value(30));
+    drop(reporter);
+
+    let collected = collected.lock();
+    assert_eq!(2, collected.len());
+    assert_eq!(Some("users".to_owned()), collected[0].meta_data.tag);
+    assert_eq!(2, collected[0].get_total_count());
+    assert_eq!(None, collected[1].meta_data.tag);
+    assert_eq!(1, collected[1].get_total_count());
+    assert!(collected[1].meta_data.start_timestamp.is_some());
+    assert!(collected[1].meta_data.end_timestamp.is_some());
+}
+
+#[test]
+fn reports_every_interval_without_losing_values() {
+    let recorder = Arc::new(recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap());
+    let (collected, sink) = collecting_sink();
+    let reporter = Reporter::start(Duration::from_millis(10), vec![(None, recorder.clone())], vec![sink]).unwrap();
+    for value in 1..=100 {
+        succ!(recorder.record_value(value));
+    }
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while collected.lock().len() < 3 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(1));
+    }
+    for value in 1..=100 {
+        succ!(recorder.record_value(value));
+    }
+    succ!(reporter.shutdown());
+
+    let collected = collected.lock();
+    assert!(collected.len() >= 4);
+    assert_eq!(200, collected.iter().map(|h| h.get_total_count()).sum::<u64>());
+}
+
+#[test]
+fn shutdown_returns_the_first_sink_error() {
+    let recorder = Arc::new(recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap());
+    let failing = |_: &Histogram<u64>| Err(io::Error::other("sink failed"));
+    let reporter = Reporter::start(Duration::from_secs(3600), vec![(None, recorder)], vec![Box::new(failing)]).unwrap();
+    assert_eq!("sink failed", reporter.shutdown().unwrap_err().to_string());
+    assert!(Reporter::start(Duration::from_secs(0), Vec::<(_, Arc<recorder::ResizableRecorder>)>::new(), vec![]).is_err());
+}
+
+#[derive(Default)]
+struct FlipEvents {
+    latencies: Vec<Duration>,
+    timeouts: Vec<usize>,
+    counts: Vec<u64>,
+}
+
+struct FlipSink(Arc<Mutex<FlipEvents>>);
+
+impl IntervalSink for FlipSink {
+    fn write_interval(&mut self, histogram: &Histogram<u64>) -> io::Result<()> {
+        self.0.lock().counts.push(histogram.get_total_count());
+        Ok(())
+    }
+
+    fn write_flip_latency(&mut self, tag: Option<&str>, latency: Duration) -> io::Result<()> {
+        assert_eq!(Some("stuck"), tag);
+        self.0.lock().latencies.push(latency);
+        Ok(())
+    }
+
+    fn write_flip_timeout(&mut self, tag: Option<&str>, writers_in_flight: usize) -> io::Result<()> {
+        assert_eq!(Some("stuck"), tag);
+        self.0.lock().timeouts.push(writers_in_flight);
+        Ok(())
+    }
+}
+
+#[test]
+fn reports_writers_that_miss_the_flip_deadline() {
+    let recorder = Arc::new(recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap());
+    let events = Arc::new(Mutex::new(FlipEvents::default()));
+    let reporter = Reporter::start(
+        Duration::from_millis(10),
+        vec![(Some("stuck".to_owned()), recorder.clone())],
+        vec![Box::new(FlipSink(events.clone()))],
+    ).unwrap();
+
+    // a batch keeps its writer critical section open until it is dropped
+    let batch = recorder.begin_batch();
+    succ!(batch.record_value(42));
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while events.lock().timeouts.len() < 2 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(1));
+    }
+    drop(batch);
+    succ!(reporter.shutdown());
+
+    let events = events.lock();
+    assert!(events.timeouts.len() >= 2);
+    assert!(events.timeouts.iter().all(|&writers_in_flight| writers_in_flight == 1));
+    assert_eq!(events.counts.len(), events.latencies.len());
+    assert_eq!(1, events.counts.iter().sum::<u64>());
+    // the late sample reports the whole time its writer was stuck
+    assert!(events.latencies.iter().any(|&latency| latency >= Duration::from_millis(20)));
+}
+
+#[test]
+fn hgrm_sink_writes_percentile_distribution() {
+    let mut histogram = Histogram::<u64>::with_high_sigvdig(HIGHEST_TRACKABLE, 3).unwrap();
+    for value in 1..=1000 {
+        succ!(histogram.record_value(value * 1000));
+    }
+    histogram.meta_data.set_tag_string("latency".to_owned());
+    histogram.meta_data.set_time_unit(TimeUnit::Microseconds);
+    let mut out = Vec::new();
+    {
+        let mut sink = HgrmSink::new(&mut out, TimeUnit::Milliseconds);
+        succ!(sink.write_interval(&histogram));
+        succ!(sink.flush());
+    }
+    let out = String::from_utf8(out).unwrap();
+    let lines: Vec<_> = out.lines().collect();
+    assert_eq!("#[Tag = latency]", lines[0]);
+    assert_eq!("       Value     Percentile TotalCount 1/(1-Percentile)", lines[1]);
+    assert_eq!("", lines[2]);
+    assert_eq!("       1.000 0.000000000000          1           1.00", lines[3]);
+    let last_value_line = lines.iter().rev().find(|line| !line.starts_with('#') && !line.is_empty()).unwrap();
+    assert_eq!("    1000.447 1.000000000000       1000", *last_value_line);
+    assert!(out.contains("#[Max     =     1000.447, Total count    =         1000]\n"));
+    assert!(out.contains("#[Buckets =           22, SubBuckets     =         2048]\n"));
+}
+
+#[test]
+fn hgrm_sink_writes_histograms_without_time_unit_unscaled() {
+    let mut histogram = Histogram::<u64>::with_high_sigvdig(HIGHEST_TRACKABLE, 3).unwrap();
+    succ!(histogram.record_value(1500));
+    let mut out = Vec::new();
+    succ!(HgrmSink::new(&mut out, TimeUnit::Milliseconds).write_interval(&histogram));
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("#[Max     =     1500.000, Total count    =            1]\n"));
+}
+
+#[cfg(feature = "encoding-base64")]
+#[test]
+fn hlog_sink_writes_header_and_tagged_intervals() {
+    let base_time = SystemTime::now();
+    let mut histogram = Histogram::<u64>::with_high_sigvdig(HIGHEST_TRACKABLE, 3).unwrap();
+    succ!(histogram.record_value(1_500_000));
+    histogram.meta_data.set_start_timestamp(base_time + Duration::from_millis(1500));
+    histogram.meta_data.set_end_timestamp(base_time + Duration::from_millis(2500));
+    histogram.meta_data.set_tag_string("latency".to_owned());
+    histogram.meta_data.set_time_unit(TimeUnit::Nanoseconds);
+    let mut out = Vec::new();
+    {
+        let mut sink = HlogSink::new(&mut out, base_time, TimeUnit::Milliseconds).unwrap();
+        succ!(sink.write_interval(&histogram));
+        succ!(sink.flush());
+    }
+    let out = String::from_utf8(out).unwrap();
+    let lines: Vec<_> = out.lines().collect();
+    assert_eq!(5, lines.len());
+    assert_eq!("#[Histogram log format version 1.3]", lines[0]);
+    assert!(lines[1].starts_with("#[StartTime: "));
+    assert!(lines[2].starts_with("#[BaseTime: "));
+    assert_eq!("\"StartTimestamp\",\"Interval_Length\",\"Interval_Max\",\"Interval_Compressed_Histogram\"", lines[3]);
+    let fields: Vec<_> = lines[4].split(',').collect();
+    assert_eq!(vec!["Tag=latency", "1.500", "1.000", "1.500"], fields[..4].to_vec());
+    let decoded: Histogram<u64> = encoding::decode_compressed_base64(fields[4]).unwrap();
+    assert!(decoded.equals(&histogram));
+    assert_eq!(Some(1), decoded.get_count_at_value(1_500_000));
+}
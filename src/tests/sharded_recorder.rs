@@ -0,0 +1,77 @@
+use crate::concurrent::ShardedRecorder;
+use std::sync::Arc;
+use std::thread;
+
+const HIGHEST_TRACKABLE: u64 = 3600 * 1000 * 1000;
+
+#[test]
+fn merges_shards_of_all_threads() {
+    let recorder = Arc::new(ShardedRecorder::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap());
+    succ!(recorder.record_value(1));
+    let writers: Vec<_> = (0..4_u64)
+        .map(|thread| {
+            let recorder = recorder.clone();
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    succ!(recorder.record_value(1000 * (thread + 1)));
+                }
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    assert_eq!(5, recorder.shard_count());
+    let sample = recorder.sample().unwrap();
+    assert_eq!(4001, sample.get_total_count());
+    assert_eq!(1, sample.get_min_value());
+    assert!(sample.values_are_equivalent(4000, sample.get_max_value()));
+    assert_eq!(Some(1000), sample.get_count_at_value(3000));
+    assert!(sample.meta_data.end_timestamp.is_some());
+
+    // the exited threads' shards were dropped once merged
+    assert_eq!(1, recorder.shard_count());
+    succ!(recorder.record_value_with_expected_interval(400, 100));
+    let sample = recorder.sample().unwrap();
+    assert_eq!(4, sample.get_total_count());
+    assert!(sample.meta_data.start_timestamp.is_some());
+}
+
+#[test]
+fn no_values_lost_across_samples() {
+    let recorder = Arc::new(ShardedRecorder::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 2).unwrap());
+    let writers: Vec<_> = (0..4)
+        .map(|_| {
+            let recorder = recorder.clone();
+            thread::spawn(move || {
+                for value in 0..50_000 {
+                    succ!(recorder.record_value_with_count(value % 1000 + 1, 2));
+                }
+            })
+        })
+        .collect();
+
+    let mut total = 0;
+    while !writers.iter().all(|w| w.is_finished()) {
+        total += recorder.sample().unwrap().get_total_count();
+    }
+    for writer in writers {
+        writer.join().unwrap();
+    }
+    total += recorder.sample().unwrap().get_total_count();
+    assert_eq!(400_000, total);
+    assert_eq!(0, recorder.shard_count());
+}
+
+#[test]
+fn separate_recorders_use_separate_shards() {
+    let a = ShardedRecorder::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap();
+    let b = ShardedRecorder::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap();
+    succ!(a.record_value(10));
+    succ!(b.record_value(20));
+    succ!(b.record_value(20));
+    assert_eq!(1, a.sample().unwrap().get_total_count());
+    assert_eq!(2, b.sample().unwrap().get_total_count());
+    assert!(ShardedRecorder::with_low_high_sigvdig(0, HIGHEST_TRACKABLE, 3).is_err());
+}
@@ -0,0 +1,66 @@
+use crate::st::SignedHistogram;
+
+fn new_histogram() -> SignedHistogram<u64> {
+    SignedHistogram::<u64>::with_low_high_sigvdig(1, 3600 * 1000 * 1000, 3).unwrap()
+}
+
+#[test]
+fn records_both_signs() {
+    let mut histogram = new_histogram();
+    succ!(histogram.record_value(-1000));
+    succ!(histogram.record_value(-1000));
+    succ!(histogram.record_value(0));
+    succ!(histogram.record_value(500));
+    succ!(histogram.record_value(i64::MIN));
+
+    assert_eq!(5, histogram.get_total_count());
+    assert_eq!(Some(2), histogram.get_count_at_value(-1000));
+    assert_eq!(Some(1), histogram.get_count_at_value(0));
+    assert_eq!(Some(0), histogram.get_count_at_value(1000));
+    assert_eq!(i64::MIN, histogram.get_min_value());
+    assert_eq!(500, histogram.get_max_value());
+}
+
+#[test]
+fn percentiles_span_signed_range() {
+    let mut histogram = new_histogram();
+    for value in -500..500 {
+        succ!(histogram.record_value(value));
+    }
+    assert_eq!(-500, histogram.get_value_at_percentile(0.0));
+    assert_eq!(-401, histogram.get_value_at_percentile(10.0));
+    assert_eq!(-1, histogram.get_value_at_percentile(50.0));
+    assert_eq!(0, histogram.get_value_at_percentile(50.1));
+    assert_eq!(499, histogram.get_value_at_percentile(100.0));
+    assert_eq!(vec![-401, 499], histogram.get_values_at_percentiles(&[10.0, 100.0]));
+
+    assert_approx_eq!(histogram.get_percentile_at_or_below_value(-401), 10.0, 0.0001);
+    assert_approx_eq!(histogram.get_percentile_at_or_below_value(-1), 50.0, 0.0001);
+    assert_approx_eq!(histogram.get_percentile_at_or_below_value(499), 100.0, 0.0001);
+    assert_approx_eq!(histogram.get_mean(), -0.5, 0.0001);
+}
+
+#[test]
+fn only_negative_values() {
+    let mut histogram = new_histogram();
+    succ!(histogram.record_value(-20));
+    succ!(histogram.record_value(-10));
+    assert_eq!(-20, histogram.get_min_value());
+    assert_eq!(-10, histogram.get_max_value());
+    assert_eq!(-20, histogram.get_value_at_percentile(50.0));
+    assert_eq!(-10, histogram.get_value_at_percentile(100.0));
+    assert_approx_eq!(histogram.get_mean(), -15.0, 0.0001);
+}
+
+#[test]
+fn recorded_values_iterate_in_signed_order() {
+    let mut histogram = new_histogram();
+    for value in [3, -7, 0, -2, 3, 12].iter() {
+        succ!(histogram.record_value(*value));
+    }
+    let values = histogram.recorded_values().collect::<Vec<_>>();
+    let iterated = values.iter().map(|v| (v.value_iterated_to, v.count_at_value_iterated_to)).collect::<Vec<_>>();
+    assert_eq!(vec![(-7, 1), (-2, 1), (0, 1), (3, 2), (12, 1)], iterated);
+    assert_eq!(6, values.last().unwrap().total_count_to_this_value);
+    assert_approx_eq!(values.last().unwrap().percentile, 100.0, 0.0001);
+}
@@ -0,0 +1,87 @@
+use crate::concurrent::SingleWriterRecorder;
+use std::thread;
+
+const HIGHEST_TRACKABLE: u64 = 3600 * 1000 * 1000;
+
+#[test]
+fn samples_contain_values_since_previous_sample() {
+    let mut recorder = SingleWriterRecorder::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap();
+    let sampler = recorder.sampler();
+    succ!(recorder.record_value(100));
+    succ!(recorder.record_value_with_count(200, 3));
+
+    let sample = sampler.locking_sample();
+    assert_eq!(4, sample.get_total_count());
+    assert_eq!(Some(3), sample.get_count_at_value(200));
+    assert!(sample.meta_data.start_timestamp.is_some());
+    assert!(sample.meta_data.end_timestamp.is_some());
+    drop(sample);
+
+    succ!(recorder.record_value_with_expected_interval(1000, 250));
+    let sample = sampler.locking_sample();
+    assert_eq!(4, sample.get_total_count());
+    assert_eq!(250, sample.get_min_value());
+    assert!(sample.meta_data.start_timestamp.is_some());
+
+    let sample = sample.resample();
+    assert_eq!(0, sample.get_total_count());
+}
+
+#[test]
+fn auto_resize_carries_over() {
+    let mut histogram = crate::st::Histogram::<u64>::with_low_high_sigvdig(1, 2, 3).unwrap();
+    histogram.set_auto_resize(true);
+    let mut recorder = SingleWriterRecorder::from_histogram(histogram);
+    let sampler = recorder.sampler();
+    // the value lands in the fresh histogram swapped in by this sample
+    drop(sampler.locking_sample());
+    succ!(recorder.record_value(HIGHEST_TRACKABLE));
+    assert_eq!(1, sampler.locking_sample().get_total_count());
+}
+
+#[test]
+fn no_values_lost_across_samples() {
+    let mut recorder = SingleWriterRecorder::with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 2).unwrap();
+    let sampler = recorder.sampler();
+    let writer = thread::spawn(move || {
+        for value in 0..200_000 {
+            succ!(recorder.record_value(value % 1000 + 1));
+        }
+    });
+
+    let mut total = 0;
+    let mut sample = sampler.locking_sample();
+    while !writer.is_finished() {
+        total += sample.get_total_count();
+        sample = sample.resample();
+    }
+    total += sample.get_total_count();
+    drop(sample);
+    writer.join().unwrap();
+    total += sampler.locking_sample().get_total_count();
+    assert_eq!(200_000, total);
+}
+
+#[test]
+fn sampling_while_the_writer_resizes() {
+    let mut histogram = crate::st::Histogram::<u64>::with_low_high_sigvdig(1, 2, 2).unwrap();
+    histogram.set_auto_resize(true);
+    let mut recorder = SingleWriterRecorder::from_histogram(histogram);
+    let sampler = recorder.sampler();
+    let writer = thread::spawn(move || {
+        // every sample starts from the creation settings, so each shift resizes again
+        for _ in 0..200 {
+            for shift in 1..40 {
+                succ!(recorder.record_value(1 << shift));
+            }
+        }
+    });
+
+    let mut total = 0;
+    while !writer.is_finished() {
+        total += sampler.locking_sample().get_total_count();
+    }
+    writer.join().unwrap();
+    total += sampler.locking_sample().get_total_count();
+    assert_eq!(200 * 39, total);
+}
@@ -0,0 +1,80 @@
+use crate::concurrent::recorder;
+use crate::core::*;
+use crate::st::SlidingWindowHistogram;
+
+const HIGHEST_TRACKABLE: u64 = 3600 * 1000 * 1000;
+
+fn new_window(interval_count: usize) -> SlidingWindowHistogram<u64> {
+    SlidingWindowHistogram::<u64>::with_low_high_sigvdig(interval_count, 1, HIGHEST_TRACKABLE, 3).unwrap()
+}
+
+#[test]
+fn rotation_evicts_oldest_interval() {
+    let mut window = new_window(3);
+    succ!(window.record_value(100));
+    succ!(window.rotate());
+    succ!(window.record_value(200));
+    succ!(window.record_value(200));
+    succ!(window.rotate());
+    succ!(window.record_value(300));
+    assert_eq!(4, window.get_total_count());
+    assert_eq!(100, window.get_value_at_percentile(0.0));
+    assert_eq!(1, window.current_interval().get_total_count());
+
+    succ!(window.rotate());
+    assert_eq!(3, window.get_total_count());
+    assert_eq!(200, window.get_value_at_percentile(0.0));
+    assert_eq!(0, window.current_interval().get_total_count());
+
+    succ!(window.rotate());
+    succ!(window.rotate());
+    assert_eq!(0, window.get_total_count());
+    assert_eq!(0, window.get_max_value());
+}
+
+#[test]
+fn aggregate_matches_sum_of_intervals() {
+    let mut window = new_window(4);
+    for tick in 0..10_u64 {
+        for value in 0..100 {
+            succ!(window.record_value(tick * 1000 + value));
+        }
+        succ!(window.rotate());
+    }
+    // the window holds ticks 7, 8, 9 and the empty current interval
+    assert_eq!(300, window.get_total_count());
+    assert_eq!(7000, window.get_value_at_percentile(0.0));
+    assert!(window.aggregate().values_are_equivalent(9099, window.get_max_value()));
+}
+
+#[test]
+fn window_over_recorder_samples() {
+    let recorder = recorder::resizable_with_low_high_sigvdig(1, HIGHEST_TRACKABLE, 3).unwrap();
+    let mut window = new_window(2);
+
+    succ!(recorder.record_value(1));
+    let mut sample = recorder.locking_sample();
+
+    succ!(recorder.record_value(10));
+    succ!(recorder.record_value(10));
+    sample = sample.resample_into_window(&mut window).unwrap();
+    assert_eq!(2, window.get_total_count());
+    assert_eq!(10, window.get_value_at_percentile(0.0));
+
+    succ!(recorder.record_value(20));
+    sample = sample.resample_into_window(&mut window).unwrap();
+    assert_eq!(3, window.get_total_count());
+
+    succ!(recorder.record_value(30));
+    let _sample = sample.resample_into_window(&mut window).unwrap();
+    assert_eq!(2, window.get_total_count());
+    assert_eq!(20, window.get_value_at_percentile(0.0));
+}
+
+#[test]
+fn zero_interval_count_is_rejected() {
+    assert_eq!(
+        Some(SlidingWindowCreationError::IntervalCountIsZero),
+        SlidingWindowHistogram::<u64>::with_low_high_sigvdig(0, 1, HIGHEST_TRACKABLE, 3).err()
+    );
+}
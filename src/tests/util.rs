@@ -12,6 +12,15 @@ macro_rules! assert_approx_eq {
     }
 }
 
+// every value from `low` to `high` inclusive, recorded once
+pub fn uniform_histogram(low: u64, high: u64, significant_value_digits: u8) -> Histogram<u64> {
+    let mut histogram = Histogram::<u64>::with_low_high_sigvdig(1, 3600 * 1000 * 1000, significant_value_digits).unwrap();
+    for v in low..=high {
+        succ!(histogram.record_value(v));
+    }
+    histogram
+}
+
 pub fn stat_histo() -> Histogram<u64> {
     let mut histogram = Histogram::<u64>::with_low_high_sigvdig(1, 3600 * 1000 * 1000, 3).unwrap();
     for _ in 0..10000 {
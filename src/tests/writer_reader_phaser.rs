@@ -0,0 +1,138 @@
+use crate::concurrent::writer_reader_phaser::{WaitStrategy, WriterReaderPhaser};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Barrier;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const WRITER_DELAY: Duration = Duration::from_millis(10);
+
+#[test]
+fn begun_flip_completes_once_writers_leave() {
+    let phaser = WriterReaderPhaser::new();
+    let writer = phaser.begin_writer_critical_section();
+
+    let guard = phaser.reader_lock();
+    assert!(phaser.try_reader_lock().is_none());
+    let pending = guard.begin_flip();
+    assert!(!guard.is_flip_complete(&pending));
+
+    // writers entering after the flip don't hold it up
+    let late_writer = phaser.begin_writer_critical_section();
+    writer.end_writer_critical_section();
+    assert!(guard.is_flip_complete(&pending));
+    late_writer.end_writer_critical_section();
+
+    guard.reader_unlock();
+    assert!(phaser.try_reader_lock().is_some());
+}
+
+#[test]
+fn reader_lock_is_released_on_the_thread_the_guard_moved_to() {
+    let phaser = WriterReaderPhaser::new();
+    let guard = phaser.reader_lock();
+    thread::scope(|scope| {
+        let waiting_reader = scope.spawn(|| phaser.reader_lock().reader_unlock());
+        scope.spawn(move || {
+            guard.flip();
+            guard.reader_unlock();
+        });
+        waiting_reader.join().unwrap();
+    });
+    assert!(phaser.try_reader_lock().is_some());
+}
+
+#[test]
+fn try_flip_until_reports_writers_in_flight() {
+    let phaser = WriterReaderPhaser::new();
+    let first = phaser.begin_writer_critical_section();
+    let second = phaser.begin_writer_critical_section();
+
+    let guard = phaser.reader_lock();
+    let timeout = guard
+        .try_flip_until(Instant::now() + Duration::from_millis(5), WaitStrategy::Yield)
+        .unwrap_err();
+    assert_eq!(2, timeout.writers_in_flight);
+
+    first.end_writer_critical_section();
+    let timeout = guard
+        .wait_for_flip_until(&timeout.pending, Instant::now(), WaitStrategy::Yield)
+        .unwrap_err();
+    assert_eq!(1, timeout.writers_in_flight);
+
+    second.end_writer_critical_section();
+    assert_eq!(0, guard.writers_in_flight(&timeout.pending));
+    assert!(guard
+        .wait_for_flip_until(&timeout.pending, Instant::now(), WaitStrategy::Yield)
+        .is_ok());
+    assert!(guard.try_flip_until(Instant::now(), WaitStrategy::Park).is_ok());
+}
+
+#[test]
+fn every_wait_strategy_waits_for_writers() {
+    let strategies = [
+        WaitStrategy::Yield,
+        WaitStrategy::Sleep(Duration::from_millis(1)),
+        WaitStrategy::SpinThenYield(100),
+        WaitStrategy::ExponentialBackoff {
+            initial: Duration::from_micros(10),
+            max: Duration::from_millis(1),
+        },
+        WaitStrategy::Park,
+    ];
+    for strategy in strategies {
+        let phaser = WriterReaderPhaser::new();
+        let entered = Barrier::new(2);
+        let left = AtomicBool::new(false);
+        let latency = thread::scope(|scope| {
+            scope.spawn(|| {
+                let writer = phaser.begin_writer_critical_section();
+                entered.wait();
+                thread::sleep(WRITER_DELAY);
+                left.store(true, Ordering::SeqCst);
+                writer.end_writer_critical_section();
+            });
+            entered.wait();
+            phaser.reader_lock().flip_with_wait_strategy(strategy)
+        });
+        assert!(left.load(Ordering::SeqCst), "{:?} returned before the writer left", strategy);
+        assert!(latency >= WRITER_DELAY / 2, "{:?} reported {:?}", strategy, latency);
+    }
+}
+
+#[test]
+fn timed_flips_report_how_long_writers_took() {
+    let phaser = WriterReaderPhaser::new();
+    let guard = phaser.reader_lock();
+    guard.flip();
+    guard.flip_with_yield_time(Duration::from_millis(1));
+    thread::scope(|scope| {
+        let writer = phaser.begin_writer_critical_section();
+        scope.spawn(move || {
+            thread::sleep(WRITER_DELAY);
+            writer.end_writer_critical_section();
+        });
+        assert!(guard.timed_flip_with_yield_time(Duration::from_millis(1)) >= WRITER_DELAY / 2);
+    });
+    assert!(guard.timed_flip() < WRITER_DELAY);
+}
+
+#[test]
+fn parked_reader_is_woken_by_the_last_writer() {
+    let phaser = WriterReaderPhaser::new();
+    let stop = AtomicBool::new(false);
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                while !stop.load(Ordering::Relaxed) {
+                    let _writer = phaser.begin_writer_critical_section();
+                    thread::yield_now();
+                }
+            });
+        }
+        // a missed wakeup would leave the reader parked forever
+        for _ in 0..1000 {
+            phaser.reader_lock().flip_with_wait_strategy(WaitStrategy::Park);
+        }
+        stop.store(true, Ordering::Relaxed);
+    });
+}